@@ -3,10 +3,11 @@ use std::str::FromStr;
 
 use log::{info, error, debug, LevelFilter};
 use lotus_miner_lib::{
-    logger::{LoggerConfig, init_global_logger},
+    logger::{ChannelFullPolicy, LoggerConfig, Formatter, auto_console_formatter, plain_formatter, init_global_logger},
     ConfigSettings, Server,
-    miner::{KernelType, Miner, MiningSettings, Work},
-    create_genesis_block, update_genesis_timestamp, update_genesis_nonce, get_current_timestamp,
+    miner::{GpuSelection, KernelType, Miner, MiningSettings, Work},
+    settings::MiningBackend,
+    create_genesis_block, update_genesis_timestamp, update_genesis_nonce, get_current_timestamp, GenesisConfig,
 };
 use clap::Parser;
 
@@ -47,9 +48,10 @@ struct Cli {
     #[clap(short, long, value_name = "CONFIG", help = "Configuration file (TOML)")]
     config: Option<String>,
 
-    /// GPU index to use for mining (default: 0)
-    #[clap(short = 'g', long, value_name = "gpu_index", help = "GPU index to use (default: 0)")]
-    gpu_index: Option<i64>,
+    /// GPU index/indices to use for mining: a single index, a comma-separated
+    /// list (e.g. "0,1,3") to mine on several devices at once, or "all" (default: 0)
+    #[clap(short = 'g', long, value_name = "gpu_index", help = "GPU index, comma-separated list, or \"all\" (default: 0)")]
+    gpu_index: Option<String>,
 
     /// Kernel size (work batch size, default: 23)
     #[clap(short = 's', long, value_name = "kernel_size", help = "Kernel size (default: 23)")]
@@ -63,20 +65,34 @@ struct Cli {
     #[clap(short = 'o', long, value_name = "mine_to_address", help = "Coinbase Output Address (mine-to address)")]
     mine_to_address: Option<String>,
 
-    /// Password for Lotus RPC authentication
-    #[clap(short = 'p', long, value_name = "rpc_password", help = "Lotus RPC password")]
+    /// Password for Lotus RPC authentication. For a `stratum+tcp://` pool,
+    /// this doubles as the Stratum worker password.
+    #[clap(short = 'p', long, value_name = "rpc_password", help = "Lotus RPC password (Stratum worker password with stratum+tcp://)")]
     rpc_password: Option<String>,
 
     /// How often to poll the Lotus node for new work (seconds)
     #[clap(short = 'i', long, value_name = "rpc_poll_interval", help = "Lotus RPC getblocktemplate poll interval (seconds)")]
     rpc_poll_interval: Option<i64>,
 
-    /// Lotus node RPC URL (e.g. http://127.0.0.1:10604 or https://burnlotus.org)
-    #[clap(short = 'a', long, value_name = "rpc_url", help = "Lotus RPC address")]
+    /// Lotus node RPC URL(s). A single URL (e.g. http://127.0.0.1:10604), or a
+    /// comma-separated list (e.g. "https://a.example,https://b.example") to
+    /// fail over across if the active one stops responding. With
+    /// `--poolmining`, a `stratum+tcp://host:port` URL instead drives the
+    /// native Stratum v1 client against a pool.
+    #[clap(short = 'a', long, value_name = "rpc_url", help = "Lotus RPC address(es), comma-separated for failover; or stratum+tcp://host:port with --poolmining")]
     rpc_url: Option<String>,
 
-    /// Username for Lotus RPC authentication
-    #[clap(short = 'u', long, value_name = "rpc_user", help = "Lotus RPC username")]
+    /// Timeout for establishing an RPC connection, in seconds (default: 5)
+    #[clap(long = "rpc-connect-timeout", value_name = "seconds", help = "RPC connect timeout in seconds (default: 5)")]
+    rpc_connect_timeout: Option<i64>,
+
+    /// Timeout for a full RPC request/response round trip, in seconds (default: 15)
+    #[clap(long = "rpc-request-timeout", value_name = "seconds", help = "RPC request timeout in seconds (default: 15)")]
+    rpc_request_timeout: Option<i64>,
+
+    /// Username for Lotus RPC authentication. For a `stratum+tcp://` pool,
+    /// this doubles as the Stratum worker name.
+    #[clap(short = 'u', long, value_name = "rpc_user", help = "Lotus RPC username (Stratum worker name with stratum+tcp://)")]
     rpc_user: Option<String>,
 
     /// Enable pool mining mode (submit shares to a pool instead of solo mining)
@@ -94,6 +110,27 @@ struct Cli {
     /// Difficulty bits for genesis mining (hex format, e.g., 0x1c100000 for testnet)
     #[clap(long = "genesis-bits", value_name = "bits", help = "Difficulty bits for genesis mining (default: 0x1c100000)")]
     genesis_bits: Option<String>,
+
+    /// Also write a machine-readable genesis config (JSON or TOML, chosen by
+    /// the path's extension; defaults to JSON) alongside the usual .txt dump
+    #[clap(long = "genesis-out", value_name = "path", help = "Write a structured genesis config to path (.json or .toml)")]
+    genesis_out: Option<String>,
+
+    /// Shorthand for enabling the metrics/status endpoint at "host:port",
+    /// overriding the `[metrics]` section of the config file.
+    #[clap(long = "stats-bind", value_name = "host:port", help = "Enable the metrics endpoint on host:port (e.g. 127.0.0.1:9090)")]
+    stats_bind: Option<String>,
+
+    /// Sweep kernel_size/local_work_size per device at startup and keep the
+    /// fastest combination, caching the result in tuning.json
+    #[clap(long = "autotune", help = "Auto-tune kernel_size/local_work_size per device at startup")]
+    autotune: bool,
+
+    /// Select the mining backend: 'auto' (default, GPU if available else
+    /// CPU), 'gpu' (fail if no OpenCL device is found), or 'cpu' (always
+    /// use the multithreaded CPU fallback)
+    #[clap(long = "mining-backend", value_name = "mining_backend", help = "Mining backend to use: 'auto', 'gpu', or 'cpu' (default: auto)")]
+    mining_backend: Option<String>,
 }
 
 #[tokio::main]
@@ -105,9 +142,18 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         console_output: true,
         file_output: true,
         log_file_path: Some("lotus-miner.log".into()),
+        file_json: false,
+        console_formatter: auto_console_formatter(),
+        file_formatter: Formatter::new(plain_formatter),
+        max_file_bytes: Some(10 * 1024 * 1024),
+        max_rotated_files: 5,
         max_log_entries: 1000,
         max_hashrate_entries: 1000,
         level: if cli.debug { LevelFilter::Debug } else { LevelFilter::Info },
+        tag_levels: std::collections::HashMap::new(),
+        channel_bound: 4096,
+        channel_policy: ChannelFullPolicy::Block,
+        keep_duration: None,
     };
 
     // Initialize the global logger
@@ -115,10 +161,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     info!("Author: Alexandre Guillioud - FrenchBTC - https://burnlotus.org - alexandre@burnlotus.org - https://github.com/Boblepointu/lotusd");
     info!("🌸 Lotus GPU Miner CLI started");
     
-    // Load configuration, giving priority to CLI args
-    let mut settings = ConfigSettings::load(true)
+    // Load configuration (default config.toml, then --config if given, then
+    // environment), giving priority to explicit CLI args applied below.
+    let mut settings = ConfigSettings::load(true, cli.config.as_deref())
         .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)?;
-    if let Some(ref v) = cli.gpu_index { settings.gpu_index = *v; }
+    if let Some(ref v) = cli.gpu_index {
+        match GpuSelection::from_str(v) {
+            Ok(selection) => settings.gpu_index = selection,
+            Err(e) => error!("Invalid --gpu-index value: {}. Keeping configured value.", e),
+        }
+    }
     if let Some(ref v) = cli.kernel_size { settings.kernel_size = *v; }
     if let Some(ref v) = cli.kernel_type {
         settings.kernel_type = match KernelType::from_str(v) {
@@ -133,20 +185,38 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     if let Some(ref v) = cli.rpc_password { settings.rpc_password = v.clone(); }
     if let Some(ref v) = cli.rpc_poll_interval { settings.rpc_poll_interval = *v; }
     if let Some(ref v) = cli.rpc_url { settings.rpc_url = v.clone(); }
+    if let Some(ref v) = cli.rpc_connect_timeout { settings.rpc_connect_timeout = *v; }
+    if let Some(ref v) = cli.rpc_request_timeout { settings.rpc_request_timeout = *v; }
     if let Some(ref v) = cli.rpc_user { settings.rpc_user = v.clone(); }
+    if let Some(ref v) = cli.stats_bind {
+        match v.rsplit_once(':') {
+            Some((host, port)) => match port.parse::<u16>() {
+                Ok(port) => {
+                    settings.metrics.enable = true;
+                    settings.metrics.host = host.to_string();
+                    settings.metrics.port = port;
+                }
+                Err(e) => error!("Invalid --stats-bind port {:?}: {}. Keeping configured value.", port, e),
+            },
+            None => error!("Invalid --stats-bind value {:?}: expected \"host:port\". Keeping configured value.", v),
+        }
+    }
     if cli.pool_mining { settings.pool_mining = true; }
     if cli.genesis_mining { settings.genesis_mining = true; }
-    if let Some(ref v) = cli.genesis_bits { settings.genesis_bits = Some(v.clone()); }
-    if let Some(ref _v) = cli.config {
-        // Optionally, reload config from the specified file (not implemented here for brevity)
-        // You can add logic to load from a custom config file if needed.
+    if cli.autotune { settings.autotune = true; }
+    if let Some(ref v) = cli.mining_backend {
+        match MiningBackend::from_str(v) {
+            Ok(backend) => settings.mining_backend = backend,
+            Err(e) => error!("Invalid --mining-backend value: {}. Keeping configured value.", e),
+        }
     }
+    if let Some(ref v) = cli.genesis_bits { settings.genesis_bits = Some(v.clone()); }
     info!("✅ Configuration loaded successfully");
     
     // Check if genesis mining mode is enabled
     if settings.genesis_mining {
         info!("🌱 Genesis mining mode enabled!");
-        return run_genesis_mining(settings, cli.debug).await;
+        return run_genesis_mining(settings, cli.debug, cli.genesis_out.clone()).await;
     }
     
     // Add debug logs for configuration settings
@@ -159,11 +229,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         debug!("  - RPC URL: {}", settings.rpc_url);
         debug!("  - RPC Poll Interval: {}", settings.rpc_poll_interval);
         debug!("  - Pool Mining: {}", settings.pool_mining);
+        debug!("  - Autotune: {}", settings.autotune);
+        debug!("  - Mining Backend: {:?}", settings.mining_backend);
     }
 
     // Start mining
-    let report_interval = Duration::from_secs(5);
-    info!("⏱️ Reporting hashrate every {} seconds (using 60s moving average with 15s warm-up period)", report_interval.as_secs());
+    let report_interval = Duration::from_secs(settings.report_interval.max(1) as u64);
+    info!("⏱️ Reporting shares/hashrate every {} seconds (using 60s moving average with 15s warm-up period)", report_interval.as_secs());
     
     if cli.debug {
         info!("🔍 Debug mode enabled - showing detailed RPC logs");
@@ -202,7 +274,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
 }
 
 /// Genesis mining mode - mines a new genesis block with current timestamp
-async fn run_genesis_mining(settings: ConfigSettings, debug: bool) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+async fn run_genesis_mining(settings: ConfigSettings, debug: bool, genesis_out: Option<String>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     info!("🌱 Starting Genesis Block Mining Mode");
     info!("═══════════════════════════════════════════════════════════════");
     info!("📋 Genesis mining will create a new genesis block with:");
@@ -237,29 +309,38 @@ async fn run_genesis_mining(settings: ConfigSettings, debug: bool) -> Result<(),
     info!("✅ Genesis block structure created (header: {} bytes, body: {} bytes)", 
           genesis_block.header.len(), genesis_block.body.len());
     
-    // Setup GPU miner
-    info!("🔧 Initializing GPU miner...");
-    let mining_settings = MiningSettings {
-        local_work_size: 256,
-        inner_iter_size: 16,
-        kernel_size: 1 << settings.kernel_size,
-        sleep: 0,
-        gpu_indices: vec![settings.gpu_index as usize],
-        kernel_type: settings.kernel_type,
-    };
-    
-    let mut miner = Miner::setup(mining_settings.clone())
-        .map_err(|e| format!("Failed to setup miner: {:?}", e))?;
-    
-    info!("✅ GPU miner initialized successfully");
+    // Setup one GPU miner per configured device, mirroring Server::from_config's
+    // fan-out so genesis mining gets the same multi-GPU speedup as normal mining.
+    let gpu_indices = settings.gpu_index.resolve();
+    info!("🔧 Initializing GPU miner(s) on device(s): {:?}...", gpu_indices);
+    let mut miners: Vec<Miner> = gpu_indices
+        .iter()
+        .map(|&gpu_index| {
+            let profile = settings.gpu_profile(gpu_index);
+            let mining_settings = MiningSettings {
+                local_work_size: profile.local_work_size.unwrap_or(256),
+                inner_iter_size: 16,
+                kernel_size: 1 << profile.kernel_size.unwrap_or(settings.kernel_size),
+                sleep: 0,
+                gpu_indices: vec![gpu_index],
+                kernel_type: profile.kernel_type.unwrap_or(settings.kernel_type),
+            };
+            Miner::setup(mining_settings).map_err(|e| format!("Failed to setup miner: {:?}", e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let num_devices = miners.len().max(1);
+    let nonce_span = u64::MAX / num_devices as u64;
+
+    info!("✅ {} GPU miner(s) initialized successfully", miners.len());
     info!("🚀 Starting genesis mining...");
     info!("═══════════════════════════════════════════════════════════════");
-    
+
     let start_time = std::time::Instant::now();
     let mut total_hashes: u64 = 0;
+    let mut device_hashes: Vec<u64> = vec![0; miners.len()];
     let mut last_timestamp_update = std::time::Instant::now();
     let mut mining_rounds: u64 = 0;
-    
+
     loop {
         // Check if we should update the timestamp (every 30 seconds)
         if last_timestamp_update.elapsed() > Duration::from_secs(30) {
@@ -269,16 +350,9 @@ async fn run_genesis_mining(settings: ConfigSettings, debug: bool) -> Result<(),
             info!("🕐 Updated timestamp to: {} ({})", new_time,
                   chrono::DateTime::from_timestamp(new_time as i64, 0).unwrap().format("%Y-%m-%d %H:%M:%S UTC"));
         }
-        
-        // Create work from genesis block header
-        let mut work = Work::from_header(genesis_block.header, target);
-        
-        // Generate random nonce base
-        let nonce_base: u64 = rand::random();
-        work.set_big_nonce(nonce_base);
-        
+
         mining_rounds += 1;
-        
+
         // Log periodic status (every 100 rounds or in debug mode)
         if debug || mining_rounds % 100 == 0 {
             let elapsed = start_time.elapsed();
@@ -287,34 +361,74 @@ async fn run_genesis_mining(settings: ConfigSettings, debug: bool) -> Result<(),
             } else {
                 0.0
             };
-            
-            info!("⛏️  Round {}: nonce_base={:#018x}, hashes={}, hashrate={:.2} MH/s, runtime={}s",
-                  mining_rounds, nonce_base, format_number(total_hashes), 
+
+            info!("⛏️  Round {}: hashes={}, hashrate={:.2} MH/s, runtime={}s",
+                  mining_rounds, format_number(total_hashes),
                   hashrate / 1_000_000.0, elapsed.as_secs());
+            if miners.len() > 1 {
+                let breakdown: Vec<String> = device_hashes
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, hashes)| format!("GPU{}: {}", idx, format_number(*hashes)))
+                    .collect();
+                info!("📊 Per-device hashes: {}", breakdown.join(", "));
+            }
+        }
+
+        // Search every device's own partition of the nonce space in parallel,
+        // the same disjoint-range split `mine_across_devices` uses in Server.
+        let header = genesis_block.header;
+        let results: Vec<(Option<(u64, bool)>, u64)> = std::thread::scope(|scope| {
+            let handles: Vec<_> = miners
+                .iter_mut()
+                .enumerate()
+                .map(|(device_idx, miner)| {
+                    let mut work = Work::from_header(header, target);
+                    let base_nonce = nonce_span.saturating_mul(device_idx as u64);
+                    let random_offset: u64 = rand::random::<u64>() % nonce_span.max(1);
+                    work.set_big_nonce(base_nonce.saturating_add(random_offset));
+                    scope.spawn(move || {
+                        miner
+                            .find_nonce(&work, &lotus_miner_lib::Log::new())
+                            .map(|nonce| (nonce, miner.num_nonces_per_search()))
+                            .unwrap_or((None, 0))
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        let mut winning_nonce = None;
+        for (device_idx, (nonce, num_nonces)) in results.into_iter().enumerate() {
+            total_hashes += num_nonces;
+            device_hashes[device_idx] += num_nonces;
+            if winning_nonce.is_none() {
+                winning_nonce = nonce;
+            }
         }
-        
+
         // Mine on this work
-        match miner.find_nonce(&work, &lotus_miner_lib::Log::new()) {
-            Ok(Some(found_nonce)) => {
+        match winning_nonce {
+            Some((found_nonce, _is_block)) => {
                 // Found a solution!
                 info!("═══════════════════════════════════════════════════════════════");
                 info!("🎉 GENESIS BLOCK FOUND!");
                 info!("═══════════════════════════════════════════════════════════════");
-                
+
                 // Update the header with the winning nonce
                 update_genesis_nonce(&mut genesis_block.header, found_nonce);
-                
+
                 // Compute the final block hash
                 use lotus_miner_lib::sha256::lotus_hash;
                 let block_hash = lotus_hash(&genesis_block.header);
                 let mut display_hash = block_hash.clone();
                 display_hash.reverse();
-                
+
                 info!("✨ Winning nonce: {}", found_nonce);
                 info!("🔗 Block hash: {}", hex::encode(&display_hash));
                 info!("🕐 Timestamp: {}", get_timestamp_from_header(&genesis_block.header));
                 info!("⏱️  Mining time: {:.2} seconds", start_time.elapsed().as_secs_f64());
-                info!("💯 Total hashes: {}", format_number(total_hashes + miner.num_nonces_per_search()));
+                info!("💯 Total hashes: {}", format_number(total_hashes));
                 info!("═══════════════════════════════════════════════════════════════");
                 info!("📝 Genesis block parameters for chainparams.cpp:");
                 info!("═══════════════════════════════════════════════════════════════");
@@ -364,16 +478,36 @@ async fn run_genesis_mining(settings: ConfigSettings, debug: bool) -> Result<(),
                     Ok(_) => info!("💾 Genesis block saved to: {}", filename),
                     Err(e) => error!("❌ Failed to save genesis block: {}", e),
                 }
-                
+
+                // Optionally also emit a structured, re-validatable genesis
+                // config instead of leaving tooling to parse the .txt dump.
+                if let Some(ref path) = genesis_out {
+                    let config = GenesisConfig {
+                        bits: genesis_bits,
+                        timestamp: get_timestamp_from_header(&genesis_block.header),
+                        nonce: found_nonce,
+                        target: hex::encode(&target),
+                        block_hash: hex::encode(&display_hash),
+                        merkle_root: hex::encode(get_merkle_root_from_header(&genesis_block.header)),
+                        header_hex: hex::encode(&genesis_block.header),
+                        body_hex: hex::encode(&genesis_block.body),
+                        block_size: 160 + genesis_block.body.len() as u64,
+                    };
+                    let rendered = if path.ends_with(".toml") {
+                        config.render_toml()
+                    } else {
+                        config.render_json()
+                    };
+                    match std::fs::write(path, rendered) {
+                        Ok(_) => info!("💾 Genesis config saved to: {}", path),
+                        Err(e) => error!("❌ Failed to save genesis config to {}: {}", path, e),
+                    }
+                }
+
                 return Ok(());
             }
-            Ok(None) => {
-                // No solution found in this batch, continue mining
-                total_hashes += miner.num_nonces_per_search();
-            }
-            Err(e) => {
-                error!("❌ Mining error: {:?}", e);
-                return Err(format!("Mining error: {:?}", e).into());
+            None => {
+                // No solution found in this batch across any device, continue mining
             }
         }
     }
@@ -426,6 +560,16 @@ fn get_timestamp_from_header(header: &[u8; 160]) -> u64 {
     ])
 }
 
+/// Extract the merkle root from a genesis block header, converting it from
+/// the internal (reversed) storage form back to display/comparison order.
+fn get_merkle_root_from_header(header: &[u8; 160]) -> [u8; 32] {
+    let offset = 96; // hashPrevBlock(32) + nBits(4) + vTime(6) + nReserved(2) + nNonce(8) + nHeaderVersion(1) + vSize(7) + nHeight(4) + hashEpochBlock(32)
+    let mut merkle_root = [0u8; 32];
+    merkle_root.copy_from_slice(&header[offset..offset + 32]);
+    merkle_root.reverse();
+    merkle_root
+}
+
 /// Format a number with thousand separators
 fn format_number(value: u64) -> String {
     let s = value.to_string();
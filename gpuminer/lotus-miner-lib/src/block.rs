@@ -1,8 +1,20 @@
 use std::convert::TryInto;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use serde::Deserialize;
 use thiserror::Error;
 
+use crate::genesis_miner::{get_current_timestamp, update_genesis_nonce, update_genesis_timestamp};
+use crate::pow::{header_hash, header_meets_target};
+
+/// How many nonces a worker thread tries between checks of whether the
+/// header timestamp is due for a refresh. The shared "someone already found
+/// it" flag and the wall-clock deadline are checked on every nonce.
+const NONCES_PER_CHECK: u64 = 200_000_000;
+
 /// Errors that can occur during block parsing
 #[derive(Debug, Error)]
 pub enum BlockParseError {
@@ -88,7 +100,7 @@ impl Block {
     pub fn prev_hash(&self) -> &[u8] {
         &self.header[..32]
     }
-    
+
     pub fn empty() -> Self {
         Block {
             header: [0; 160],
@@ -96,12 +108,92 @@ impl Block {
             target: [0; 32],
         }
     }
-    
+
     pub fn body_size(&self) -> usize {
         self.body.len()
     }
-    
+
     pub fn get_body(&self) -> &[u8] {
         &self.body
     }
+
+    /// The double-SHA256 block hash in conventional big-endian display form.
+    pub fn hash(&self) -> [u8; 32] {
+        header_hash(&self.header)
+    }
+
+    /// `nHeight`, stored little-endian at byte offset 60 of the header
+    /// (after hashPrevBlock, nBits, vTime, nReserved, nNonce, nHeaderVersion, vSize).
+    pub fn height(&self) -> i32 {
+        i32::from_le_bytes(self.header[60..64].try_into().unwrap())
+    }
+}
+
+/// Search the full 64-bit nonce space for a header that satisfies `block.target`,
+/// spreading the search across `num_cpus::get()` worker threads.
+///
+/// Thread `k` of `n` tries nonces `k, k + n, k + 2n, ...`. The first thread to
+/// find a winning nonce writes it into `block.header` and signals the others
+/// through `found`; the function then returns that nonce. If `max_seconds` is
+/// `Some`, the search gives up and returns `None` once the deadline elapses so
+/// the caller can fetch fresh work from the node.
+pub fn mine_block(block: &mut Block, max_seconds: Option<u64>) -> Option<u64> {
+    let num_threads = num_cpus::get().max(1) as u64;
+    let found = Arc::new(AtomicBool::new(false));
+    let deadline = max_seconds.map(|secs| Instant::now() + Duration::from_secs(secs));
+
+    let header = block.header;
+    let target = block.target;
+
+    let winner: Arc<std::sync::Mutex<Option<(u64, [u8; 160])>>> = Arc::new(std::sync::Mutex::new(None));
+
+    thread::scope(|scope| {
+        for thread_idx in 0..num_threads {
+            let found = Arc::clone(&found);
+            let winner = Arc::clone(&winner);
+            scope.spawn(move || {
+                let mut local_header = header;
+                let mut nonce = thread_idx;
+                let mut last_timestamp_refresh = Instant::now();
+
+                loop {
+                    for _ in 0..NONCES_PER_CHECK {
+                        if found.load(Ordering::Relaxed) {
+                            return;
+                        }
+                        if let Some(deadline) = deadline {
+                            if Instant::now() >= deadline {
+                                return;
+                            }
+                        }
+
+                        update_genesis_nonce(&mut local_header, nonce);
+                        if header_meets_target(&local_header, &target) {
+                            if !found.swap(true, Ordering::SeqCst) {
+                                *winner.lock().unwrap() = Some((nonce, local_header));
+                            }
+                            return;
+                        }
+
+                        nonce = nonce.wrapping_add(num_threads);
+                    }
+
+                    // Keep the timestamp fresh on long-running searches and
+                    // reset the per-batch counters implicitly via the outer loop.
+                    if last_timestamp_refresh.elapsed() > Duration::from_secs(30) {
+                        update_genesis_timestamp(&mut local_header, get_current_timestamp());
+                        last_timestamp_refresh = Instant::now();
+                    }
+                }
+            });
+        }
+    });
+
+    let winner = winner.lock().unwrap().take();
+    if let Some((nonce, winning_header)) = winner {
+        block.header = winning_header;
+        Some(nonce)
+    } else {
+        None
+    }
 }
@@ -0,0 +1,128 @@
+use std::sync::Mutex;
+use std::time::Instant;
+
+use eyre::Result;
+use log::debug;
+
+use crate::miner::{self, Backend, Work};
+use crate::sha256::lotus_hash;
+use crate::Log;
+
+/// Multithreaded CPU mining backend, used when `mining_backend` is `cpu`, or
+/// under `auto` when no OpenCL device is found (see `Server::from_config`). Mirrors
+/// engraver's `cpu_hasher`: each `find_nonce` call splits a fixed-size batch
+/// of the nonce range evenly across `threads` worker threads, each calling
+/// `lotus_hash` directly (no OpenCL kernel involved) and checking the result
+/// against `work`'s targets, same as `Miner::scan_buffer` does for a GPU
+/// readback.
+pub struct CpuMiner {
+    threads: usize,
+    /// Total nonces searched per `find_nonce` call, split evenly across
+    /// `threads`. Plays the same role as `MiningSettings::kernel_size`, just
+    /// much smaller since a CPU core is orders of magnitude slower than a
+    /// GPU's thousands of lanes.
+    batch_size: u32,
+}
+
+impl CpuMiner {
+    /// Build a CPU backend using every hardware thread the platform reports
+    /// (falling back to a single thread if it can't).
+    pub fn new() -> Self {
+        let threads = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        log::info!("CPU mining backend using {} thread(s)", threads);
+        CpuMiner {
+            threads,
+            batch_size: 1 << 16,
+        }
+    }
+}
+
+impl Default for CpuMiner {
+    fn default() -> Self {
+        CpuMiner::new()
+    }
+}
+
+impl Backend for CpuMiner {
+    fn num_nonces_per_search(&self) -> u64 {
+        self.batch_size as u64
+    }
+
+    fn has_nonces_left(&self, work: &Work) -> bool {
+        work.nonce_idx.checked_mul(self.batch_size).is_some()
+    }
+
+    fn find_nonce(&mut self, work: &Work, log: &Log) -> Result<Option<(u64, bool)>> {
+        let base = match work.nonce_idx.checked_mul(self.batch_size) {
+            Some(base) => base as u64,
+            None => {
+                log.error(
+                    "Error: Nonce base overflow, skipping. This could be fixed by lowering rpc_poll_interval.",
+                    Some("Miner"),
+                );
+                return Ok(None);
+            }
+        };
+
+        let batch_start = Instant::now();
+        let per_thread = ((self.batch_size as u64) / self.threads as u64).max(1);
+        let winner: Mutex<Option<(u64, bool)>> = Mutex::new(None);
+        let header_template = *work.header();
+        let share_target = *work.share_target();
+        let block_target = *work.block_target();
+
+        std::thread::scope(|scope| {
+            for thread_idx in 0..self.threads {
+                let winner = &winner;
+                let thread_base = base + thread_idx as u64 * per_thread;
+                let thread_count = if thread_idx + 1 == self.threads {
+                    (self.batch_size as u64).saturating_sub(per_thread * thread_idx as u64)
+                } else {
+                    per_thread
+                };
+
+                scope.spawn(move || {
+                    let mut header = header_template;
+                    for offset in 0..thread_count {
+                        if winner.lock().unwrap().is_some() {
+                            return;
+                        }
+                        let nonce = thread_base + offset;
+                        header[44..52].copy_from_slice(&nonce.to_le_bytes());
+                        let hash = lotus_hash(&header);
+                        if miner::hash_meets_target(&hash, &share_target) {
+                            let is_block = miner::hash_meets_target(&hash, &block_target);
+                            let shares = miner::record_share();
+                            log.info(
+                                format!("Found valid share #{} at nonce {} (CPU)", shares, nonce),
+                                Some("Share"),
+                            );
+                            if is_block {
+                                log.info(
+                                    "This share also clears the full block target!",
+                                    Some("Share"),
+                                );
+                            }
+                            *winner.lock().unwrap() = Some((nonce, is_block));
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+
+        miner::record_hashes(self.batch_size as u64);
+
+        let batch_time = batch_start.elapsed();
+        let speed = if batch_time.as_secs_f64() > 0.0 {
+            self.batch_size as f64 / batch_time.as_secs_f64() / 1_000_000.0
+        } else {
+            0.0
+        };
+        if work.nonce_idx % 100 == 0 {
+            debug!("CPU batch speed: {:.2} MH/s | {}", speed, miner::mining_runtime_stats());
+        }
+
+        Ok(winner.into_inner().unwrap())
+    }
+}
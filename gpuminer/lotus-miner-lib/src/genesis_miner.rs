@@ -5,6 +5,9 @@
 use sha2::{Sha256, Digest};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::block::Block;
+use crate::pow::nbits_to_target;
+
 // Constants from lotusd (src/amount.h and src/consensus/consensus.h)
 const SATOSHI: i64 = 1;
 const LOTUS: i64 = 1_000_000 * SATOSHI;
@@ -27,27 +30,29 @@ const GENESIS_OUTPUT_1_PUBKEY: &str = "04678afdb0fe5548271967f1a67130b7105cd6a82
 
 /// Represents a transaction input (CTxIn in lotusd)
 #[derive(Clone)]
-struct TxIn {
-    prevout_hash: [u8; 32],
-    prevout_n: u32,
-    script_sig: Vec<u8>,
-    sequence: u32,
+pub struct TxIn {
+    pub prevout_hash: [u8; 32],
+    pub prevout_n: u32,
+    pub script_sig: Vec<u8>,
+    pub sequence: u32,
 }
 
 /// Represents a transaction output (CTxOut in lotusd)
 #[derive(Clone)]
-struct TxOut {
-    value: i64,  // Amount in satoshis
-    script_pubkey: Vec<u8>,
+pub struct TxOut {
+    pub value: i64,  // Amount in satoshis
+    pub script_pubkey: Vec<u8>,
 }
 
-/// Represents a transaction (CMutableTransaction in lotusd)
+/// Represents a transaction (CMutableTransaction in lotusd). Public so a
+/// caller assembling a block template (see [`CandidateTx::Built`]) can
+/// build one directly instead of only supplying pre-serialized bytes.
 #[derive(Clone)]
-struct Transaction {
-    version: i32,
-    vin: Vec<TxIn>,
-    vout: Vec<TxOut>,
-    lock_time: u32,
+pub struct Transaction {
+    pub version: i32,
+    pub vin: Vec<TxIn>,
+    pub vout: Vec<TxOut>,
+    pub lock_time: u32,
 }
 
 /// Represents a genesis block
@@ -64,7 +69,7 @@ impl Transaction {
     /// - std::vector<CTxIn> vin
     /// - std::vector<CTxOut> vout
     /// - uint32_t nLockTime
-    fn serialize(&self) -> Vec<u8> {
+    pub fn serialize(&self) -> Vec<u8> {
         let mut data = Vec::new();
         
         // nVersion (4 bytes, little endian)
@@ -107,7 +112,7 @@ impl Transaction {
     }
     
     /// Compute transaction hash (double SHA256)
-    fn get_hash(&self) -> [u8; 32] {
+    pub fn get_hash(&self) -> [u8; 32] {
         let serialized = self.serialize();
         let hash1 = Sha256::digest(&serialized);
         let hash2 = Sha256::digest(&hash1);
@@ -115,9 +120,9 @@ impl Transaction {
         result.copy_from_slice(&hash2);
         result
     }
-    
+
     /// Compute transaction ID (same as hash for version 1)
-    fn get_id(&self) -> [u8; 32] {
+    pub fn get_id(&self) -> [u8; 32] {
         self.get_hash()
     }
 }
@@ -147,15 +152,28 @@ fn encode_compact_size(size: usize) -> Vec<u8> {
 ///     CScript() << OP_RETURN << COINBASE_PREFIX << nHeight
 ///               << ParseHex("ffe330c4b7643e554c62adcbe0b80537435d888b5c33d5e29a70cdd743e3a093");
 fn build_genesis_output_0_script(height: i32) -> Vec<u8> {
+    let mut script = build_op_return_height_script(height);
+
+    // Address hash (32 bytes)
+    let hash = hex::decode(GENESIS_OUTPUT_0_HASH).expect("Invalid genesis output 0 hash");
+    script.push(hash.len() as u8);
+    script.extend_from_slice(&hash);
+
+    script
+}
+
+/// Build the `OP_RETURN <COINBASE_PREFIX> <nHeight>` prefix shared by every
+/// coinbase's output 0, genesis or otherwise.
+fn build_op_return_height_script(height: i32) -> Vec<u8> {
     let mut script = Vec::new();
-    
+
     // OP_RETURN
     script.push(OP_RETURN);
-    
+
     // COINBASE_PREFIX (5 bytes, push as data)
     script.push(COINBASE_PREFIX.len() as u8);
     script.extend_from_slice(COINBASE_PREFIX);
-    
+
     // nHeight (encoded as compact integer)
     if height == 0 {
         script.push(0x00); // OP_0 for height 0
@@ -165,12 +183,7 @@ fn build_genesis_output_0_script(height: i32) -> Vec<u8> {
         script.push(height_bytes.len() as u8);
         script.extend_from_slice(&height_bytes);
     }
-    
-    // Address hash (32 bytes)
-    let hash = hex::decode(GENESIS_OUTPUT_0_HASH).expect("Invalid genesis output 0 hash");
-    script.push(hash.len() as u8);
-    script.extend_from_slice(&hash);
-    
+
     script
 }
 
@@ -257,61 +270,112 @@ fn create_genesis_transaction() -> Transaction {
 /// For each tx, create a leaf: Hash(tx.GetHash() || tx.GetId())
 /// Then compute the merkle tree
 fn compute_merkle_root(txs: &[Transaction]) -> [u8; 32] {
-    if txs.is_empty() {
+    let leaves: Vec<([u8; 32], [u8; 32])> = txs.iter().map(|tx| (tx.get_hash(), tx.get_id())).collect();
+    compute_merkle_root_from_leaves(&leaves)
+}
+
+/// Build the Lotus merkle root from pre-computed `(tx.GetHash(), tx.GetId())`
+/// pairs. Shared by the genesis-specific path and the general block
+/// assembler, where external candidate transactions may already carry a
+/// node-supplied hash instead of one computed by [`Transaction::serialize`].
+fn compute_merkle_root_from_leaves(leaves: &[([u8; 32], [u8; 32])]) -> [u8; 32] {
+    if leaves.is_empty() {
         return [0u8; 32];
     }
-    
-    // Create leaves: Hash(tx.GetHash() || tx.GetId())
-    // Note: In lotusd, uint256 hashes are serialized in little-endian (reversed from hash output)
-    let mut hashes: Vec<[u8; 32]> = txs.iter().map(|tx| {
-        let mut leaf_data = Vec::new();
-        // Reverse transaction hashes to match lotusd's uint256 serialization format
-        let mut tx_hash = tx.get_hash();
-        tx_hash.reverse();
-        let mut tx_id = tx.get_id();
-        tx_id.reverse();
-        leaf_data.extend_from_slice(&tx_hash);
-        leaf_data.extend_from_slice(&tx_id);
-        
-        let hash = Sha256::digest(&Sha256::digest(&leaf_data));
-        let mut result = [0u8; 32];
-        result.copy_from_slice(&hash);
-        result
-    }).collect();
-    
+
+    let mut hashes: Vec<[u8; 32]> = leaves.iter().map(|(tx_hash, tx_id)| compute_merkle_leaf(*tx_hash, *tx_id)).collect();
+
     // Build merkle tree
     while hashes.len() > 1 {
         let mut next_level = Vec::new();
-        
-        // Process pairs
+
+        // Process pairs; a lone node at the end is paired with the
+        // all-zero hash rather than duplicated, unlike classic Bitcoin.
         for i in (0..hashes.len()).step_by(2) {
-            if i + 1 < hashes.len() {
-                // Hash pair
-                let mut pair_data = Vec::new();
-                pair_data.extend_from_slice(&hashes[i]);
-                pair_data.extend_from_slice(&hashes[i + 1]);
-                let hash = Sha256::digest(&Sha256::digest(&pair_data));
-                let mut result = [0u8; 32];
-                result.copy_from_slice(&hash);
-                next_level.push(result);
-            } else {
-                // Odd one out, pair with null hash
-                let mut pair_data = Vec::new();
-                pair_data.extend_from_slice(&hashes[i]);
-                pair_data.extend_from_slice(&[0u8; 32]);
-                let hash = Sha256::digest(&Sha256::digest(&pair_data));
-                let mut result = [0u8; 32];
-                result.copy_from_slice(&hash);
-                next_level.push(result);
-            }
+            let right = if i + 1 < hashes.len() { hashes[i + 1] } else { [0u8; 32] };
+            next_level.push(combine_merkle_pair(hashes[i], right));
         }
-        
+
         hashes = next_level;
     }
-    
+
     hashes[0]
 }
 
+/// Form a merkle leaf exactly as lotusd does: `Hash(reverse(tx.GetHash()) || reverse(tx.GetId()))`.
+pub(crate) fn compute_merkle_leaf(tx_hash: [u8; 32], tx_id: [u8; 32]) -> [u8; 32] {
+    let mut leaf_data = Vec::new();
+    // Reverse transaction hashes to match lotusd's uint256 serialization format
+    let mut tx_hash = tx_hash;
+    tx_hash.reverse();
+    let mut tx_id = tx_id;
+    tx_id.reverse();
+    leaf_data.extend_from_slice(&tx_hash);
+    leaf_data.extend_from_slice(&tx_id);
+    compute_serialize_hash(&leaf_data)
+}
+
+/// Combine two sibling merkle nodes into their parent.
+pub(crate) fn combine_merkle_pair(left: [u8; 32], right: [u8; 32]) -> [u8; 32] {
+    let mut pair_data = Vec::new();
+    pair_data.extend_from_slice(&left);
+    pair_data.extend_from_slice(&right);
+    compute_serialize_hash(&pair_data)
+}
+
+/// Compute the merkle branch (sibling path) for the transaction at `index`,
+/// so its inclusion in the block's merkle root can later be verified
+/// without the full transaction list. `leaves` are `(tx.GetHash(), tx.GetId())`
+/// pairs, the same shape [`compute_merkle_root_from_leaves`] takes.
+///
+/// Reproduces this tree's odd-node rule: a lone node at a level is paired
+/// with the all-zero hash, not duplicated. Each step records the sibling of
+/// the node at the running index; the caller tracks left/right by the low
+/// bit of that same index at each level (see [`verify_merkle_branch`]).
+pub fn merkle_branch(leaves: &[([u8; 32], [u8; 32])], index: usize) -> Vec<[u8; 32]> {
+    let mut hashes: Vec<[u8; 32]> = leaves.iter().map(|(tx_hash, tx_id)| compute_merkle_leaf(*tx_hash, *tx_id)).collect();
+    let mut branch = Vec::new();
+    let mut idx = index;
+
+    while hashes.len() > 1 {
+        let sibling_idx = idx ^ 1;
+        let sibling = hashes.get(sibling_idx).copied().unwrap_or([0u8; 32]);
+        branch.push(sibling);
+
+        let mut next_level = Vec::new();
+        for i in (0..hashes.len()).step_by(2) {
+            let right = if i + 1 < hashes.len() { hashes[i + 1] } else { [0u8; 32] };
+            next_level.push(combine_merkle_pair(hashes[i], right));
+        }
+        hashes = next_level;
+        idx /= 2;
+    }
+
+    branch
+}
+
+/// Verify a merkle branch produced by [`merkle_branch`] against `root`.
+///
+/// `leaf` must be formed exactly as the root-building code does (see
+/// [`compute_merkle_leaf`]); at each level, whether `index`'s low bit is 0
+/// or 1 decides whether the running hash is the left or right child when
+/// combined with the corresponding branch entry.
+pub fn verify_merkle_branch(leaf: [u8; 32], branch: &[[u8; 32]], index: usize, root: [u8; 32]) -> bool {
+    let mut current = leaf;
+    let mut idx = index;
+
+    for sibling in branch {
+        current = if idx & 1 == 0 {
+            combine_merkle_pair(current, *sibling)
+        } else {
+            combine_merkle_pair(*sibling, current)
+        };
+        idx /= 2;
+    }
+
+    current == root
+}
+
 /// Serialize extended metadata (empty for genesis block)
 fn serialize_extended_metadata() -> Vec<u8> {
     // Empty metadata for genesis block
@@ -320,7 +384,7 @@ fn serialize_extended_metadata() -> Vec<u8> {
 }
 
 /// Compute hash of serialized data using double SHA256
-fn compute_serialize_hash(data: &[u8]) -> [u8; 32] {
+pub(crate) fn compute_serialize_hash(data: &[u8]) -> [u8; 32] {
     let hash1 = Sha256::digest(data);
     let hash2 = Sha256::digest(&hash1);
     let mut result = [0u8; 32];
@@ -346,7 +410,7 @@ fn serialize_block_body(metadata: &[u8], txs: &[Transaction]) -> Vec<u8> {
     body
 }
 
-/// Build the complete genesis block header (160 bytes)
+/// Build a complete block header (160 bytes)
 /// From lotusd/src/primitives/block.h - CBlockHeader structure:
 /// - hashPrevBlock (32 bytes)
 /// - nBits (4 bytes)
@@ -359,24 +423,30 @@ fn serialize_block_body(metadata: &[u8], txs: &[Transaction]) -> Vec<u8> {
 /// - hashEpochBlock (32 bytes)
 /// - hashMerkleRoot (32 bytes)
 /// - hashExtendedMetadata (32 bytes)
-fn build_genesis_block_header(
+pub(crate) fn build_block_header(
+    prev_hash: [u8; 32],
     n_bits: u32,
     n_time: u64,
     n_nonce: u64,
+    height: i32,
+    epoch_hash: [u8; 32],
     merkle_root: [u8; 32],
     extended_metadata_hash: [u8; 32],
     block_size: u64,
 ) -> [u8; 160] {
     let mut header = [0u8; 160];
     let mut offset = 0;
-    
-    // hashPrevBlock (32 bytes) - all zeros for genesis
+
+    // hashPrevBlock (32 bytes) - stored as internal bytes (little-endian)
+    let mut prev_hash_bytes = prev_hash;
+    prev_hash_bytes.reverse();
+    header[offset..offset + 32].copy_from_slice(&prev_hash_bytes);
     offset += 32;
-    
+
     // nBits (4 bytes, little endian)
     header[offset..offset + 4].copy_from_slice(&n_bits.to_le_bytes());
     offset += 4;
-    
+
     // vTime (6 bytes, little endian encoding of 48-bit timestamp)
     let time_bytes = [
         (n_time & 0xff) as u8,
@@ -388,18 +458,18 @@ fn build_genesis_block_header(
     ];
     header[offset..offset + 6].copy_from_slice(&time_bytes);
     offset += 6;
-    
+
     // nReserved (2 bytes) - all zeros
     offset += 2;
-    
+
     // nNonce (8 bytes, little endian)
     header[offset..offset + 8].copy_from_slice(&n_nonce.to_le_bytes());
     offset += 8;
-    
-    // nHeaderVersion (1 byte) - always 1 for genesis
+
+    // nHeaderVersion (1 byte) - always 1
     header[offset] = 1;
     offset += 1;
-    
+
     // vSize (7 bytes, little endian encoding of 56-bit size)
     let size_bytes = [
         (block_size & 0xff) as u8,
@@ -412,24 +482,28 @@ fn build_genesis_block_header(
     ];
     header[offset..offset + 7].copy_from_slice(&size_bytes);
     offset += 7;
-    
-    // nHeight (4 bytes, little endian) - 0 for genesis
+
+    // nHeight (4 bytes, little endian)
+    header[offset..offset + 4].copy_from_slice(&height.to_le_bytes());
     offset += 4;
-    
-    // hashEpochBlock (32 bytes) - all zeros for genesis
+
+    // hashEpochBlock (32 bytes) - stored as internal bytes (little-endian)
+    let mut epoch_hash_bytes = epoch_hash;
+    epoch_hash_bytes.reverse();
+    header[offset..offset + 32].copy_from_slice(&epoch_hash_bytes);
     offset += 32;
-    
+
     // hashMerkleRoot (32 bytes) - stored as internal bytes (little-endian)
     let mut merkle_root_bytes = merkle_root;
     merkle_root_bytes.reverse();  // Convert from hash output (big-endian) to internal format
     header[offset..offset + 32].copy_from_slice(&merkle_root_bytes);
     offset += 32;
-    
+
     // hashExtendedMetadata (32 bytes) - stored as internal bytes (little-endian)
     let mut extended_metadata_bytes = extended_metadata_hash;
     extended_metadata_bytes.reverse();  // Convert from hash output (big-endian) to internal format
     header[offset..offset + 32].copy_from_slice(&extended_metadata_bytes);
-    
+
     header
 }
 
@@ -457,10 +531,13 @@ pub fn create_genesis_block(n_bits: u32, n_time: u64, target: [u8; 32]) -> Genes
     let block_size = 160 + body.len() as u64;
     
     // Build the block header (with initial nonce = 0)
-    let header = build_genesis_block_header(
+    let header = build_block_header(
+        [0u8; 32], // hashPrevBlock - none for genesis
         n_bits,
         n_time,
         0, // Initial nonce
+        0, // nHeight - genesis is height 0
+        [0u8; 32], // hashEpochBlock - none for genesis
         merkle_root,
         extended_metadata_hash,
         block_size,
@@ -502,6 +579,159 @@ pub fn get_current_timestamp() -> u64 {
         .as_secs()
 }
 
+/// Configuration for assembling a real (non-genesis) block's coinbase,
+/// mirroring a block-assembler config: who gets paid, at what height, and
+/// for how much.
+pub struct CoinbaseConfig {
+    /// scriptPubKey that receives the subsidy plus fees.
+    pub recipient_script: Vec<u8>,
+    /// Height of the block being assembled; embedded in the coinbase's
+    /// OP_RETURN output exactly like the genesis coinbase embeds height 0.
+    pub height: i32,
+    /// Subsidy plus collected fees, in satoshis.
+    pub value: i64,
+}
+
+/// A transaction to include in a block template, alongside the coinbase.
+/// Candidates sourced from a node's mempool/template typically arrive as
+/// raw bytes with an already-known hash/id, so both representations are
+/// accepted.
+pub enum CandidateTx {
+    /// A transaction built with this crate's own [`Transaction`] type.
+    Built(Transaction),
+    /// An already-serialized transaction plus its precomputed hash and id
+    /// (as returned by e.g. `getblocktemplate`).
+    Raw { bytes: Vec<u8>, hash: [u8; 32], id: [u8; 32] },
+}
+
+impl CandidateTx {
+    fn serialized(&self) -> Vec<u8> {
+        match self {
+            CandidateTx::Built(tx) => tx.serialize(),
+            CandidateTx::Raw { bytes, .. } => bytes.clone(),
+        }
+    }
+
+    fn hash_and_id(&self) -> ([u8; 32], [u8; 32]) {
+        match self {
+            CandidateTx::Built(tx) => (tx.get_hash(), tx.get_id()),
+            CandidateTx::Raw { hash, id, .. } => (*hash, *id),
+        }
+    }
+}
+
+/// Build the coinbase transaction for a real block: output 0 is the
+/// `OP_RETURN <COINBASE_PREFIX> <nHeight>` marker (no address hash, unlike
+/// the genesis coinbase), output 1 pays `config.recipient_script` the full
+/// subsidy plus fees.
+fn build_coinbase_transaction(config: &CoinbaseConfig) -> Transaction {
+    let height_bytes = encode_script_int(config.height);
+    let mut script_sig = Vec::new();
+    script_sig.push(height_bytes.len() as u8);
+    script_sig.extend_from_slice(&height_bytes);
+
+    let coinbase_input = TxIn {
+        prevout_hash: [0u8; 32],
+        prevout_n: 0xffffffff,
+        script_sig,
+        sequence: 0xffffffff,
+    };
+
+    let output_0 = TxOut {
+        value: 0,
+        script_pubkey: build_op_return_height_script(config.height),
+    };
+    let output_1 = TxOut {
+        value: config.value,
+        script_pubkey: config.recipient_script.clone(),
+    };
+
+    Transaction {
+        version: 1,
+        vin: vec![coinbase_input],
+        vout: vec![output_0, output_1],
+        lock_time: 0,
+    }
+}
+
+/// Assemble a mineable [`Block`] from a coinbase configuration and an
+/// ordered list of candidate transactions, turning a node's
+/// mempool/template response into something the multi-threaded miner can
+/// search directly.
+pub fn assemble_block(
+    coinbase_config: &CoinbaseConfig,
+    prev_hash: [u8; 32],
+    epoch_hash: [u8; 32],
+    n_bits: u32,
+    n_time: u64,
+    candidate_txs: Vec<CandidateTx>,
+) -> Block {
+    let mut txs = Vec::with_capacity(candidate_txs.len() + 1);
+    txs.push(CandidateTx::Built(build_coinbase_transaction(coinbase_config)));
+    txs.extend(candidate_txs);
+
+    let leaves: Vec<([u8; 32], [u8; 32])> = txs.iter().map(CandidateTx::hash_and_id).collect();
+    let merkle_root = compute_merkle_root_from_leaves(&leaves);
+
+    let metadata = serialize_extended_metadata();
+    let extended_metadata_hash = compute_serialize_hash(&metadata);
+
+    let mut body = Vec::new();
+    body.extend_from_slice(&metadata);
+    body.extend_from_slice(&encode_compact_size(txs.len()));
+    for tx in &txs {
+        body.extend_from_slice(&tx.serialized());
+    }
+
+    let block_size = 160 + body.len() as u64;
+    let header = build_block_header(
+        prev_hash,
+        n_bits,
+        n_time,
+        0, // Initial nonce; the miner fills this in
+        coinbase_config.height,
+        epoch_hash,
+        merkle_root,
+        extended_metadata_hash,
+        block_size,
+    );
+
+    Block {
+        header,
+        body,
+        target: nbits_to_target(n_bits),
+    }
+}
+
+/// Machine-readable summary of a mined genesis block, for tooling to consume
+/// or re-validate instead of scraping the `CreateGenesisBlock(...)` snippet
+/// logged to stdout. Borrows from Solana's split of an opaque genesis block
+/// into a reusable, parseable genesis config artifact.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct GenesisConfig {
+    pub bits: u32,
+    pub timestamp: u64,
+    pub nonce: u64,
+    pub target: String,
+    pub block_hash: String,
+    pub merkle_root: String,
+    pub header_hex: String,
+    pub body_hex: String,
+    pub block_size: u64,
+}
+
+impl GenesisConfig {
+    /// Render as pretty-printed JSON.
+    pub fn render_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    /// Render as TOML.
+    pub fn render_toml(&self) -> String {
+        toml::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,15 +1,31 @@
 mod block;
 pub mod miner;
+mod cpu_miner;
 pub mod settings;
 mod sha256;
 pub mod logger;
 pub mod genesis_miner;
+pub mod pow;
+pub mod metrics;
+mod stratum;
+pub mod statistics;
+mod rpc;
+pub mod work_notify;
+pub mod worker;
 
 use eyre::Result;
-pub use miner::Miner;
+pub use miner::{Miner, MinerBackend};
+use miner::Backend;
 pub use settings::ConfigSettings;
-pub use logger::{Log, LogSeverity, HashrateEntry, LoggerConfig, init_global_logger, LogEntry};
-pub use genesis_miner::{create_genesis_block, update_genesis_timestamp, update_genesis_nonce, get_current_timestamp, GenesisBlock};
+pub use logger::{Log, LogSeverity, HashrateEntry, LoggerConfig, ChannelFullPolicy, RecordFilter, init_global_logger, LogEntry, Formatter, colored_formatter, plain_formatter, json_formatter, auto_console_formatter};
+pub use genesis_miner::{create_genesis_block, update_genesis_timestamp, update_genesis_nonce, get_current_timestamp, GenesisBlock, GenesisConfig, CoinbaseConfig, CandidateTx, assemble_block};
+pub use pow::{nbits_to_target, header_meets_target, header_hash, target_to_nbits, calculate_next_target, retarget_next_bits};
+pub use metrics::{MetricsSettings, MetricsSnapshot};
+pub use work_notify::WorkNotifySettings;
+pub use stratum::{StratumClient, StratumError, StratumEvent, StratumJob};
+pub use worker::{WorkerCommand, WorkerState, WorkerStatus};
+use statistics::Statistics;
+use worker::WorkerRegistry;
 
 use std::{
     convert::TryInto,
@@ -20,64 +36,290 @@ use std::{
     time::{Duration, SystemTime},
 };
 
-use block::{create_block, Block, GetRawUnsolvedBlockResponse};
+use block::{create_block, GetRawUnsolvedBlockResponse};
+pub use block::{mine_block, Block};
 use miner::{MiningSettings, Work};
 use rand::{Rng, SeedableRng};
 use reqwest::{RequestBuilder, StatusCode};
 use serde::Deserialize;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
 use tokio::sync::{Mutex, MutexGuard};
 
 pub struct Server {
     client: reqwest::Client,
-    miner: std::sync::Mutex<Miner>,
+    /// One backend per configured device slot, each searching its own slice
+    /// of the nonce space (see `mine_across_devices`). Usually one
+    /// `MinerBackend::Gpu` per configured GPU; a single `MinerBackend::Cpu`
+    /// when `mining_backend` falls back to the CPU (no OpenCL device found).
+    miners: Vec<std::sync::Mutex<MinerBackend>>,
+    device_nonces: Vec<AtomicU64>,
+    /// Each device's hashrate as of the last periodic report (see
+    /// `log_per_device_hashrate`), exposed through the metrics endpoint.
+    device_hashrate: Vec<std::sync::Mutex<f64>>,
     node_settings: Mutex<NodeSettings>,
     block_state: Mutex<BlockState>,
     rng: Mutex<rand::rngs::StdRng>,
-    metrics_timestamp: Mutex<SystemTime>,
-    metrics_nonces: AtomicU64,
-    hashrate_data_points: Mutex<Vec<(SystemTime, u64)>>,
-    last_total_nonces: AtomicU64,
     log: Log,
     report_hashrate_interval: Duration,
+    metrics_settings: MetricsSettings,
+    /// Hashrate and share counters, reported periodically by the background
+    /// reporting task spawned in `run`. See the `statistics` module.
+    statistics: Statistics,
+    /// Optional work-pull/work-submit endpoint for external/secondary miner
+    /// processes. See `run_work_notify_server`.
+    work_notify_settings: work_notify::WorkNotifySettings,
+    last_rpc_poll_latency: Mutex<Duration>,
+    notify_webhook_url: Option<String>,
+    notify_webhook_username: Option<String>,
+    /// When a failed-over primary RPC endpoint was last retried (see `PRIMARY_RETRY_INTERVAL`).
+    last_primary_retry: Mutex<SystemTime>,
+    /// When the server was constructed, used to report uptime via the metrics endpoint.
+    start_time: SystemTime,
+    /// Resolved Stratum pool address (scheme stripped), worker name, and
+    /// password, set when `mining_protocol = "stratum"` (or a back-compat
+    /// `stratum+tcp://` `rpc_url`); `None` takes the RPC poll path instead.
+    stratum_addr: Option<String>,
+    stratum_worker: String,
+    stratum_password: String,
+    /// The live Stratum connection. `stratum_controller` owns it (connects,
+    /// reconnects, reads pushes); `stratum_dispatch_nonces` only reaches in
+    /// to submit a found nonce, since both tasks need the one socket.
+    stratum_client: std::sync::Mutex<Option<stratum::StratumClient>>,
+    /// Current pool-assigned share difficulty's target, set by
+    /// `mining.set_difficulty` pushes. Unused on the RPC path.
+    share_target: Mutex<[u8; 32]>,
+    /// Lets an operator list/pause/resume/stop the long-running loops
+    /// spawned in `run`. See `Server::list_workers`/`Server::control_worker`.
+    worker_registry: WorkerRegistry,
+    /// Deadline for a single `update_next_block`/`submit_block` attempt; see
+    /// `with_task_timeout`.
+    task_timeout: Duration,
+}
+
+/// How many consecutive RPC failures on the active endpoint trigger a
+/// round-robin failover to the next one in [`NodeSettings::bitcoind_urls`].
+const MAX_CONSECUTIVE_RPC_ERRORS: u32 = 3;
+
+/// How often [`update_next_block`] gives a failed-over primary endpoint
+/// another chance, mirroring OpenEthereum's connection-timeout/failover behavior.
+const PRIMARY_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Starting delay for [`rpc_backoff_delay`]'s exponential backoff.
+const RPC_BACKOFF_BASE: Duration = Duration::from_millis(50);
+
+/// Cap on [`rpc_backoff_delay`]'s exponential backoff, so a long outage
+/// doesn't grow the retry delay unboundedly.
+const RPC_BACKOFF_MAX: Duration = Duration::from_secs(30);
+
+/// How many times [`submit_block`] retries a transport-level send failure
+/// before giving up on that share.
+const MAX_SUBMIT_RETRIES: u32 = 3;
+
+/// Exponential backoff with jitter for RPC retries: `RPC_BACKOFF_BASE * 2^attempt`,
+/// capped at `RPC_BACKOFF_MAX` with up to 50% extra jitter (drawn from the
+/// server's `rng`) so multiple miners hitting the same flaky endpoint don't
+/// all retry in lockstep.
+fn rpc_backoff_delay(attempt: u32, rng: &mut rand::rngs::StdRng) -> Duration {
+    let base_ms = RPC_BACKOFF_BASE.as_millis() as u64 * (1u64 << attempt.min(10));
+    let capped_ms = base_ms.min(RPC_BACKOFF_MAX.as_millis() as u64);
+    let jitter_ms = rng.gen_range(0..=capped_ms / 2);
+    Duration::from_millis(capped_ms + jitter_ms)
 }
 
 pub struct NodeSettings {
-    pub bitcoind_url: String,
+    /// Configured RPC endpoints, in failover order; index 0 is the primary.
+    pub bitcoind_urls: Vec<String>,
+    /// Index into `bitcoind_urls` of the endpoint currently in use.
+    pub active_endpoint: usize,
+    consecutive_rpc_errors: u32,
     pub bitcoind_user: String,
     pub bitcoind_password: String,
     pub rpc_poll_interval: u64,
+    pub rpc_connect_timeout: Duration,
+    pub rpc_request_timeout: Duration,
     pub miner_addr: String,
     pub pool_mining: bool,
 }
 
+impl NodeSettings {
+    /// The RPC endpoint currently in use.
+    pub fn active_url(&self) -> &str {
+        &self.bitcoind_urls[self.active_endpoint]
+    }
+
+    /// Record a successful RPC round trip, clearing the failure streak that
+    /// would otherwise trigger a failover.
+    fn record_rpc_success(&mut self) {
+        self.consecutive_rpc_errors = 0;
+    }
+
+    /// Record an RPC failure against the active endpoint; once it has failed
+    /// `MAX_CONSECUTIVE_RPC_ERRORS` times in a row, round-robin to the next
+    /// configured endpoint.
+    fn record_rpc_failure(&mut self, log: &Log) {
+        self.consecutive_rpc_errors += 1;
+        if self.bitcoind_urls.len() > 1 && self.consecutive_rpc_errors >= MAX_CONSECUTIVE_RPC_ERRORS {
+            let previous = self.active_url().to_string();
+            self.active_endpoint = (self.active_endpoint + 1) % self.bitcoind_urls.len();
+            self.consecutive_rpc_errors = 0;
+            log.warn(
+                format!(
+                    "RPC endpoint {} failed {} times in a row, failing over to {}",
+                    previous, MAX_CONSECUTIVE_RPC_ERRORS, self.active_url()
+                ),
+                Some("RPC"),
+            );
+        }
+    }
+}
+
 struct BlockState {
     current_work: Work,
     current_block: Option<Block>,
     next_block: Option<Block>,
     extra_nonce: u64,
+    /// Stratum-only: the job whose header is currently loaded into
+    /// `current_work`/`current_block`, needed by `stratum_dispatch_nonces`
+    /// to submit a found nonce back to the pool. `None` on the RPC path.
+    stratum_job: Option<StratumJobContext>,
+}
+
+/// Bookkeeping `stratum_dispatch_nonces` needs to submit a share: which job
+/// the currently loaded work came from, and the exact extranonce2 used to
+/// build its coinbase (the job_id alone isn't enough to recover it). Also
+/// carries the job's real block target, since `next_block.target` only
+/// holds the (much easier) share target and `Work::from_header` alone
+/// can't tell the two apart.
+#[derive(Clone)]
+struct StratumJobContext {
+    job_id: String,
+    extranonce2: Vec<u8>,
+    n_time: u32,
+    block_target: [u8; 32],
 }
 
 pub type ServerRef = Arc<Server>;
 
 impl Server {
     pub fn from_config(config: ConfigSettings, report_hashrate_interval: Duration) -> Self {
-        let mining_settings = MiningSettings {
-            local_work_size: 256,
-            inner_iter_size: 16,
-            kernel_size: 1 << config.kernel_size,
-            sleep: 0,
-            gpu_indices: vec![config.gpu_index as usize],
-            kernel_type: config.kernel_type,
+        let gpu_indices = config.gpu_index.resolve();
+        let log = Log::new();
+
+        // `mining_backend = "cpu"` forces the fallback; `"auto"` (the
+        // default) only falls back when no OpenCL device was found at all,
+        // so a machine with working GPU drivers behaves exactly as before.
+        let use_cpu_backend = config.mining_backend == settings::MiningBackend::Cpu
+            || (config.mining_backend == settings::MiningBackend::Auto && Miner::device_count() == 0);
+
+        let miners: Vec<std::sync::Mutex<MinerBackend>> = if use_cpu_backend {
+            log.info(
+                "No usable OpenCL device found (or CPU backend requested); falling back to CPU mining",
+                Some("Miner"),
+            );
+            vec![std::sync::Mutex::new(MinerBackend::Cpu(cpu_miner::CpuMiner::new()))]
+        } else {
+            gpu_indices
+                .iter()
+                .map(|&gpu_index| {
+                    let profile = config.gpu_profile(gpu_index);
+                    let mining_settings = MiningSettings {
+                        local_work_size: profile.local_work_size.unwrap_or(256),
+                        inner_iter_size: 16,
+                        kernel_size: 1 << profile.kernel_size.unwrap_or(config.kernel_size),
+                        sleep: 0,
+                        gpu_indices: vec![gpu_index],
+                        kernel_type: profile.kernel_type.unwrap_or(config.kernel_type),
+                    };
+                    let mut miner = Miner::setup(mining_settings).unwrap();
+
+                    // With `autotune = true`, a device gets its `kernel_size`/
+                    // `local_work_size` swept once and the winner cached in
+                    // `tuning.json`; later runs with a cache hit skip straight to
+                    // `apply_tuning` instead of re-sweeping every startup.
+                    if config.autotune {
+                        let cached = settings::load_tuning_cache()
+                            .into_iter()
+                            .find(|entry| entry.gpu_index == gpu_index as u32);
+                        match cached {
+                            Some(tuned) => {
+                                log.info(
+                                    format!(
+                                        "Using cached auto-tune result for GPU {}: kernel_size={}, local_work_size={}",
+                                        gpu_index, tuned.kernel_size, tuned.local_work_size
+                                    ),
+                                    Some("Miner"),
+                                );
+                                miner.apply_tuning(tuned.kernel_size, tuned.local_work_size as u32);
+                            }
+                            None => match miner.autotune(&log) {
+                                Ok(()) => settings::save_tuned_device(settings::TunedDevice {
+                                    gpu_index: gpu_index as u32,
+                                    kernel_size: miner.kernel_size(),
+                                    local_work_size: miner.local_work_size() as i32,
+                                }),
+                                Err(err) => log.error(
+                                    format!("Auto-tune failed for GPU {}: {:?}", gpu_index, err),
+                                    Some("Miner"),
+                                ),
+                            },
+                        }
+                    }
+
+                    std::sync::Mutex::new(MinerBackend::Gpu(miner))
+                })
+                .collect()
+        };
+        let device_nonces = miners.iter().map(|_| AtomicU64::new(0)).collect();
+        let device_hashrate = miners.iter().map(|_| std::sync::Mutex::new(0.0)).collect();
+        let rpc_connect_timeout = Duration::from_secs(config.rpc_connect_timeout.try_into().unwrap());
+        let rpc_request_timeout = Duration::from_secs(config.rpc_request_timeout.try_into().unwrap());
+        let bitcoind_urls: Vec<String> = config
+            .rpc_url
+            .split(',')
+            .map(|url| url.trim().to_string())
+            .filter(|url| !url.is_empty())
+            .collect();
+
+        // Resolve the Stratum connection, preferring the dedicated `[pool]`
+        // table; a `stratum+tcp://` rpc_url is kept as a fallback so configs
+        // written before `mining_protocol` existed keep working unchanged.
+        let stratum_addr = match config.mining_protocol {
+            settings::MiningProtocol::Stratum => config
+                .pool
+                .url
+                .clone()
+                .or_else(|| {
+                    bitcoind_urls
+                        .first()
+                        .and_then(|url| url.strip_prefix("stratum+tcp://").map(str::to_string))
+                })
+                .map(|addr| addr.trim_start_matches("stratum+tcp://").to_string()),
+            settings::MiningProtocol::Rpc if config.pool_mining => bitcoind_urls
+                .first()
+                .and_then(|url| url.strip_prefix("stratum+tcp://").map(str::to_string)),
+            settings::MiningProtocol::Rpc => None,
         };
-        let miner = Miner::setup(mining_settings.clone()).unwrap();
+        let stratum_worker = config.pool.worker_name.clone().unwrap_or_else(|| config.rpc_user.clone());
+        let stratum_password = config.pool.password.clone().unwrap_or_else(|| config.rpc_password.clone());
+
         Server {
-            miner: std::sync::Mutex::new(miner),
-            client: reqwest::Client::new(),
+            miners,
+            device_nonces,
+            device_hashrate,
+            client: reqwest::Client::builder()
+                .connect_timeout(rpc_connect_timeout)
+                .build()
+                .unwrap(),
             node_settings: Mutex::new(NodeSettings {
-                bitcoind_url: config.rpc_url.clone(),
+                bitcoind_urls,
+                active_endpoint: 0,
+                consecutive_rpc_errors: 0,
                 bitcoind_user: config.rpc_user.clone(),
                 bitcoind_password: config.rpc_password.clone(),
                 rpc_poll_interval: config.rpc_poll_interval.try_into().unwrap(),
+                rpc_connect_timeout,
+                rpc_request_timeout,
                 miner_addr: config.mine_to_address.clone(),
                 pool_mining: config.pool_mining,
             }),
@@ -86,149 +328,501 @@ impl Server {
                 current_block: None,
                 next_block: None,
                 extra_nonce: 0,
+                stratum_job: None,
             }),
             rng: Mutex::new(rand::rngs::StdRng::from_entropy()),
-            metrics_timestamp: Mutex::new(SystemTime::now()),
-            metrics_nonces: AtomicU64::new(0),
-            hashrate_data_points: Mutex::new(Vec::new()),
-            last_total_nonces: AtomicU64::new(0),
-            log: Log::new(),
+            log,
             report_hashrate_interval,
+            metrics_settings: config.metrics,
+            statistics: Statistics::new(),
+            work_notify_settings: config.work_notify,
+            last_rpc_poll_latency: Mutex::new(Duration::ZERO),
+            notify_webhook_url: config.notify_webhook_url,
+            notify_webhook_username: config.notify_webhook_username,
+            last_primary_retry: Mutex::new(SystemTime::now()),
+            start_time: SystemTime::now(),
+            stratum_addr,
+            stratum_worker,
+            stratum_password,
+            stratum_client: std::sync::Mutex::new(None),
+            share_target: Mutex::new(pow::difficulty_to_target(1.0)),
+            worker_registry: WorkerRegistry::new(),
+            task_timeout: Duration::from_secs(config.task_timeout.max(1).try_into().unwrap()),
         }
     }
 
     pub async fn run(self: ServerRef) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        // A resolved Stratum address bypasses the HTTP `getrawunsolvedblock`/
+        // `submitblock` RPC path entirely: `stratum_controller` keeps
+        // `block_state` current from pool pushes, and `stratum_dispatch_nonces`
+        // mines it and submits shares, mirroring the `update_next_block`/
+        // `mine_some_nonces` split used for the RPC path below.
+        if let Some(addr) = self.stratum_addr.clone() {
+            let t_controller = tokio::spawn({
+                let server = Arc::clone(&self);
+                let mut worker = server.worker_registry.register("stratum-controller");
+                async move {
+                    let log = server.log();
+                    loop {
+                        if worker.wait_if_paused().await.is_err() {
+                            break;
+                        }
+                        if let Err(err) = stratum_controller(Arc::clone(&server), addr.clone()).await {
+                            log.error(format!("Stratum connection lost: {:?}; reconnecting in 5s", err), Some("Stratum"));
+                            tokio::time::sleep(Duration::from_secs(5)).await;
+                        }
+                        worker.progress();
+                    }
+                    worker.mark_dead();
+                }
+            });
+            let t_dispatch = tokio::spawn({
+                let server = Arc::clone(&self);
+                let mut worker = server.worker_registry.register("stratum-dispatch");
+                async move {
+                    let log = server.log();
+                    loop {
+                        if worker.wait_if_paused().await.is_err() {
+                            break;
+                        }
+                        if let Err(err) = stratum_dispatch_nonces(Arc::clone(&server)).await {
+                            log.error(format!("stratum_dispatch_nonces error: {:?}", err), Some("Miner"));
+                        }
+                        worker.progress();
+                        tokio::time::sleep(Duration::from_micros(3)).await;
+                    }
+                    worker.mark_dead();
+                }
+            });
+            let t3 = tokio::spawn({
+                let server = Arc::clone(&self);
+                async move {
+                    if server.metrics_settings.enable {
+                        run_metrics_server(server).await;
+                    }
+                }
+            });
+            let t_work_notify = tokio::spawn({
+                let server = Arc::clone(&self);
+                async move {
+                    if server.work_notify_settings.enable {
+                        run_work_notify_server(server).await;
+                    }
+                }
+            });
+            let t_report = tokio::spawn(run_statistics_reporter(Arc::clone(&self)));
+            t_controller.await?;
+            t_dispatch.await?;
+            t3.await?;
+            t_work_notify.await?;
+            t_report.await?;
+            return Ok(());
+        }
+
         let t1 = tokio::spawn({
             let server = Arc::clone(&self);
+            let mut worker = server.worker_registry.register("rpc-poll");
             async move {
                 let log = server.log();
                 loop {
-                    if let Err(err) = update_next_block(&server).await {
-                        log.error(format!("update_next_block error: {:?}", err), Some("Miner"));
+                    if worker.wait_if_paused().await.is_err() {
+                        break;
+                    }
+                    match with_task_timeout(&server, "update_next_block", update_next_block(&server)).await {
+                        Some(Ok(())) => {}
+                        Some(Err(err)) => log.error(format!("update_next_block error: {:?}", err), Some("Miner")),
+                        None => {
+                            // Don't wait out the normal poll interval; go
+                            // straight back around for a fresh attempt.
+                            worker.progress();
+                            continue;
+                        }
+                    }
+                    worker.progress();
+                    let (rpc_poll_interval, consecutive_rpc_errors) = {
+                        let node_settings = server.node_settings.lock().await;
+                        (node_settings.rpc_poll_interval, node_settings.consecutive_rpc_errors)
+                    };
+                    if consecutive_rpc_errors > 0 {
+                        let delay = rpc_backoff_delay(consecutive_rpc_errors - 1, &mut server.rng.lock().await);
+                        log.debug(format!(
+                            "⏳ RPC backoff: retrying getrawunsolvedblock in {}ms after {} consecutive failures",
+                            delay.as_millis(), consecutive_rpc_errors
+                        ), Some("RPC"));
+                        tokio::time::sleep(delay).await;
+                    } else {
+                        tokio::time::sleep(Duration::from_secs(rpc_poll_interval)).await;
                     }
-                    let rpc_poll_interval = server.node_settings.lock().await.rpc_poll_interval;
-                    tokio::time::sleep(Duration::from_secs(rpc_poll_interval)).await;
                 }
+                worker.mark_dead();
             }
         });
         let t2 = tokio::spawn({
             let server = Arc::clone(&self);
+            let mut worker = server.worker_registry.register("mining");
             async move {
                 let log = server.log();
                 loop {
+                    if worker.wait_if_paused().await.is_err() {
+                        break;
+                    }
                     if let Err(err) = mine_some_nonces(Arc::clone(&server)).await {
                         log.error(format!("mine_some_nonces error: {:?}", err), Some("Miner"));
                     }
+                    worker.progress();
                     tokio::time::sleep(Duration::from_micros(3)).await;
                 }
+                worker.mark_dead();
+            }
+        });
+        let t3 = tokio::spawn({
+            let server = Arc::clone(&self);
+            async move {
+                if server.metrics_settings.enable {
+                    run_metrics_server(server).await;
+                }
+            }
+        });
+        let t_work_notify = tokio::spawn({
+            let server = Arc::clone(&self);
+            async move {
+                if server.work_notify_settings.enable {
+                    run_work_notify_server(server).await;
+                }
             }
         });
+        let t_report = tokio::spawn(run_statistics_reporter(Arc::clone(&self)));
         t1.await?;
         t2.await?;
+        t3.await?;
+        t_work_notify.await?;
+        t_report.await?;
         Ok(())
     }
 
+    /// Snapshot the telemetry exported by the `[metrics]` endpoint.
+    pub async fn metrics_snapshot(&self) -> MetricsSnapshot {
+        let current_difficulty_bits = {
+            let block_state = self.block_state.lock().await;
+            block_state
+                .current_block
+                .as_ref()
+                .map(|block| target_to_nbits(&block.target))
+                .unwrap_or(0)
+        };
+        MetricsSnapshot {
+            hashrate: self.statistics.moving_average_hashrate(&self.log).await,
+            accepted_shares: self.statistics.accepted(),
+            rejected_shares: self.statistics.rejected(),
+            current_difficulty_bits,
+            last_rpc_poll_latency_secs: self.last_rpc_poll_latency.lock().await.as_secs_f64(),
+            active_endpoint: self.node_settings.lock().await.active_url().to_string(),
+            uptime_secs: self.start_time.elapsed().unwrap_or(Duration::ZERO).as_secs(),
+            devices: self.device_metrics(),
+        }
+    }
+
+    /// Each configured GPU's OpenCL device indices alongside its hashrate as
+    /// of the last periodic report.
+    fn device_metrics(&self) -> Vec<metrics::DeviceMetrics> {
+        self.miners
+            .iter()
+            .enumerate()
+            .map(|(device_idx, miner)| metrics::DeviceMetrics {
+                gpu_indices: miner.lock().unwrap().gpu_indices().to_vec(),
+                hashrate: *self.device_hashrate[device_idx].lock().unwrap(),
+            })
+            .collect()
+    }
+
     pub async fn node_settings<'a>(&'a self) -> MutexGuard<'a, NodeSettings> {
         self.node_settings.lock().await
     }
 
-    pub fn miner<'a>(&'a self) -> std::sync::MutexGuard<'a, Miner> {
-        self.miner.lock().unwrap()
+    /// Every registered worker loop's name, state, and seconds since its
+    /// last reported progress, for the `/workers` query.
+    pub fn list_workers(&self) -> Vec<worker::WorkerStatus> {
+        self.worker_registry.list()
+    }
+
+    /// Pause, resume, or stop a named worker loop; `false` if no worker is
+    /// registered under that name.
+    pub fn control_worker(&self, name: &str, command: worker::WorkerCommand) -> bool {
+        self.worker_registry.send_command(name, command)
+    }
+
+    /// Snapshot the current work for the `[work_notify]` endpoint; `None` if
+    /// no work has been fetched/installed yet (e.g. still starting up).
+    async fn work_payload(&self) -> Option<work_notify::WorkPayload> {
+        let block_state = self.block_state.lock().await;
+        let block = block_state.current_block.as_ref()?;
+        Some(work_notify::WorkPayload {
+            header: hex::encode(block.header),
+            body: hex::encode(&block.body),
+            target: hex::encode(block.target),
+            height: block.height(),
+            extra_nonce: block_state.extra_nonce,
+        })
+    }
+
+    /// Reassemble a full block from an externally-found header (the body and
+    /// target are whatever this node last handed out as work, since only the
+    /// header's nonce changes during mining) and feed it into the normal
+    /// `submit_block` path.
+    async fn submit_candidate_header(&self, header_hex: &str) -> Result<(), String> {
+        let header_bytes = hex::decode(header_hex).map_err(|err| format!("invalid header hex: {}", err))?;
+        let header: [u8; 160] = header_bytes
+            .try_into()
+            .map_err(|_| "header must be exactly 160 bytes".to_string())?;
+        let block = {
+            let block_state = self.block_state.lock().await;
+            let current = block_state.current_block.as_ref().ok_or("no work to submit against yet")?;
+            Block {
+                header,
+                body: current.body.clone(),
+                target: current.target,
+            }
+        };
+        submit_block(self, &block).await.map_err(|err| err.to_string())
+    }
+
+    /// The first configured GPU's miner. Kept for callers that only care
+    /// about a representative device (e.g. webhook metadata); mining itself
+    /// dispatches across every device via `mine_across_devices`.
+    pub fn miner<'a>(&'a self) -> std::sync::MutexGuard<'a, MinerBackend> {
+        self.miners[0].lock().unwrap()
+    }
+
+    pub fn miners(&self) -> &[std::sync::Mutex<MinerBackend>] {
+        &self.miners
+    }
+
+    /// Log each device's share of the hashrate since the last report,
+    /// alongside the aggregate line from [`Log::report_hashrate`].
+    fn log_per_device_hashrate(&self, elapsed: Duration) {
+        if self.device_nonces.len() <= 1 {
+            return;
+        }
+        let elapsed_secs = elapsed.as_secs_f64().max(f64::EPSILON);
+        let breakdown: Vec<String> = self
+            .device_nonces
+            .iter()
+            .enumerate()
+            .map(|(device_idx, nonces)| {
+                let hashrate = nonces.swap(0, Ordering::AcqRel) as f64 / elapsed_secs;
+                *self.device_hashrate[device_idx].lock().unwrap() = hashrate;
+                format!("GPU{}: {}/s", device_idx, miner::format_hashes(hashrate as u64))
+            })
+            .collect();
+        self.log.info(format!("📊 Per-device hashrate: {}", breakdown.join(", ")), Some("Miner"));
     }
 
     pub fn log(&self) -> &Log {
         &self.log
     }
 
-    async fn calculate_moving_average_hashrate(&self) -> f64 {
-        let now = SystemTime::now();
-        let current_total_nonces = self.metrics_nonces.load(Ordering::Acquire);
-        let previous_total = self.last_total_nonces.swap(current_total_nonces, Ordering::AcqRel);
-        let new_nonces = current_total_nonces.saturating_sub(previous_total);
-        
-        let mut data_points = self.hashrate_data_points.lock().await;
-        data_points.push((now, new_nonces));
-        
-        let cutoff = now.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default() - Duration::from_secs(60);
-        data_points.retain(|(time, _)| {
-            time.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default() >= cutoff
-        });
-        
-        // Calculate total nonces and time span across all retained data points
-        let mut total_nonces = 0u64;
-        let oldest_timestamp = data_points.first().map(|(time, _)| *time).unwrap_or(now);
-        
-        for (_, nonces) in data_points.iter() {
-            total_nonces = total_nonces.saturating_add(*nonces);
+    /// Log the periodic share/hashrate summary; see [`Statistics::report`].
+    async fn report_statistics(&self, elapsed: Duration) {
+        self.statistics.report(&self.log).await;
+        self.log_per_device_hashrate(elapsed);
+    }
+}
+
+/// Periodically log the "shares accepted X, rejected Y, Z H/s" summary on
+/// `report_hashrate_interval`, replacing the report blocks that used to be
+/// scattered inline across `mine_some_nonces` and `stratum_dispatch_nonces`.
+async fn run_statistics_reporter(server: ServerRef) {
+    let mut last_report = SystemTime::now();
+    loop {
+        tokio::time::sleep(server.report_hashrate_interval).await;
+        let elapsed = SystemTime::now()
+            .duration_since(last_report)
+            .unwrap_or(server.report_hashrate_interval);
+        server.report_statistics(elapsed).await;
+        last_report = SystemTime::now();
+    }
+}
+
+/// Serve the metrics/status endpoint until the process exits. A `/json`
+/// request path gets the current snapshot as JSON; `/workers` lists every
+/// registered worker loop's state (see `worker::WorkerRegistry`), and
+/// `POST /workers/<name>/{pause,resume,stop}` controls one; anything else
+/// gets Prometheus text exposition format.
+async fn run_metrics_server(server: ServerRef) {
+    let (host, port) = (server.metrics_settings.host.clone(), server.metrics_settings.port);
+    let listener = match TcpListener::bind((host.as_str(), port)).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            server.log().error(
+                format!("Failed to bind metrics endpoint on {}:{}: {}", host, port, err),
+                Some("Metrics"),
+            );
+            return;
         }
-        
-        // Calculate time span in seconds
-        let time_span = now.duration_since(oldest_timestamp)
-            .unwrap_or_default()
-            .as_secs_f64()
-            .max(0.1); // Avoid division by zero by ensuring at least 0.1 seconds
-        
-        // Calculate initial hashrate (nonces per second)
-        let raw_hashrate = total_nonces as f64 / time_span;
-        
-        // Apply warm-up period stabilization logic
-        let stabilized_hashrate = if time_span < 15.0 {
-            // During warm-up period (less than 15 seconds of data)
-            
-            // Use a sliding scale that starts at a conservative estimate and gradually 
-            // approaches the raw value as we get more data
-            let warm_up_factor = (time_span / 15.0).min(1.0);
-            
-            // Use the most recent point's rate as a baseline, but cap it at a reasonable value
-            // This prevents extremely high initial readings
-            let single_point_rate = if data_points.len() > 1 {
-                let (time1, _) = data_points[data_points.len() - 1];
-                let (time2, nonces2) = data_points[data_points.len() - 2];
-                
-                let point_time_diff = time1.duration_since(time2)
-                    .unwrap_or_default()
-                    .as_secs_f64()
-                    .max(0.1);
-                
-                (nonces2 as f64) / point_time_diff
+    };
+    server.log().info(format!("📊 Metrics endpoint listening on {}:{}", host, port), Some("Metrics"));
+
+    loop {
+        let (mut stream, _) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(_) => continue,
+        };
+        let server = Arc::clone(&server);
+        tokio::spawn(async move {
+            let mut request_buf = [0u8; 1024];
+            let n = stream.read(&mut request_buf).await.unwrap_or(0);
+            let request_line = String::from_utf8_lossy(&request_buf[..n]);
+            let mut parts = request_line.split_whitespace();
+            let method = parts.next().unwrap_or("GET");
+            let path = parts.next().unwrap_or("/");
+
+            let (status, content_type, body) = if path == "/workers" {
+                (
+                    "200 OK",
+                    "application/json",
+                    serde_json::to_string(&server.list_workers()).unwrap_or_else(|_| "[]".to_string()),
+                )
+            } else if let Some(name) = path.strip_prefix("/workers/").and_then(|rest| {
+                rest.strip_suffix("/pause")
+                    .or_else(|| rest.strip_suffix("/resume"))
+                    .or_else(|| rest.strip_suffix("/stop"))
+            }) {
+                let command = if path.ends_with("/pause") {
+                    worker::WorkerCommand::Pause
+                } else if path.ends_with("/resume") {
+                    worker::WorkerCommand::Run
+                } else {
+                    worker::WorkerCommand::Stop
+                };
+                if method == "POST" && server.control_worker(name, command) {
+                    ("200 OK", "application/json", serde_json::json!({ "ok": true }).to_string())
+                } else {
+                    (
+                        "404 Not Found",
+                        "application/json",
+                        serde_json::json!({ "ok": false, "error": "no such worker" }).to_string(),
+                    )
+                }
             } else {
-                raw_hashrate
+                let snapshot = server.metrics_snapshot().await;
+                if path.starts_with("/json") {
+                    ("200 OK", "application/json", metrics::render_json(&snapshot))
+                } else {
+                    ("200 OK", "text/plain; version=0.0.4", metrics::render(&snapshot))
+                }
             };
-            
-            // Cap the initial estimate to prevent unrealistically high values
-            let capped_rate = single_point_rate.min(3_000_000_000.0); // Cap at 3 GH/s initially
-            
-            // Gradually blend between the capped initial estimate and the raw calculation
-            let result = capped_rate * (1.0 - warm_up_factor) + raw_hashrate * warm_up_factor;
-            
-            // Log that we're stabilizing the hashrate during warm-up
-            self.log.debug(
-                format!(
-                    "Stabilizing hashrate during {:.1}s warm-up period: raw {:.2} GH/s → stabilized {:.2} GH/s ({}% warm-up)",
-                    time_span,
-                    raw_hashrate / 1_000_000_000.0,
-                    result / 1_000_000_000.0,
-                    (warm_up_factor * 100.0) as u32
-                ),
-                Some("Hashrate")
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                content_type,
+                body.len(),
+                body
             );
-            
-            result
-        } else {
-            // We have enough data, use the raw calculated value
-            raw_hashrate
+            let _ = stream.write_all(response.as_bytes()).await;
+        });
+    }
+}
+
+/// Serve the optional work-notification endpoint until the process exits, so
+/// external/secondary miner processes can pull work and submit candidates
+/// through this node instead of each polling the upstream node/pool on their
+/// own. `GET /work` returns the current work as JSON; `POST /submit` takes a
+/// JSON body with a candidate `header` and funnels it into `submit_block`.
+async fn run_work_notify_server(server: ServerRef) {
+    let (host, port) = (server.work_notify_settings.host.clone(), server.work_notify_settings.port);
+    let listener = match TcpListener::bind((host.as_str(), port)).await {
+        Ok(listener) => listener,
+        Err(err) => {
+            server.log().error(
+                format!("Failed to bind work-notify endpoint on {}:{}: {}", host, port, err),
+                Some("WorkNotify"),
+            );
+            return;
+        }
+    };
+    server.log().info(format!("📡 Work-notify endpoint listening on {}:{}", host, port), Some("WorkNotify"));
+
+    loop {
+        let (mut stream, _) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(_) => continue,
         };
-        
-        stabilized_hashrate
+        let server = Arc::clone(&server);
+        tokio::spawn(async move {
+            let mut request_buf = [0u8; 4096];
+            let n = stream.read(&mut request_buf).await.unwrap_or(0);
+            let request = String::from_utf8_lossy(&request_buf[..n]);
+            let request_line = request.lines().next().unwrap_or("");
+            let mut parts = request_line.split_whitespace();
+            let method = parts.next().unwrap_or("GET");
+            let path = parts.next().unwrap_or("/");
+
+            let (status, content_type, body) = if method == "GET" && path.starts_with("/work") {
+                match server.work_payload().await {
+                    Some(payload) => ("200 OK", "application/json", work_notify::render_json(&payload)),
+                    None => (
+                        "503 Service Unavailable",
+                        "application/json",
+                        r#"{"error":"no work yet"}"#.to_string(),
+                    ),
+                }
+            } else if method == "POST" && path.starts_with("/submit") {
+                let request_body = request.split("\r\n\r\n").nth(1).unwrap_or("").trim_end_matches('\0');
+                match serde_json::from_str::<work_notify::SubmitPayload>(request_body) {
+                    Ok(submit) => match server.submit_candidate_header(&submit.header).await {
+                        Ok(()) => ("200 OK", "application/json", r#"{"status":"submitted"}"#.to_string()),
+                        Err(err) => {
+                            server.log().warn(format!("work-notify submit rejected: {}", err), Some("WorkNotify"));
+                            (
+                                "400 Bad Request",
+                                "application/json",
+                                serde_json::json!({ "error": err }).to_string(),
+                            )
+                        }
+                    },
+                    Err(err) => (
+                        "400 Bad Request",
+                        "application/json",
+                        serde_json::json!({ "error": format!("invalid submit payload: {}", err) }).to_string(),
+                    ),
+                }
+            } else {
+                ("404 Not Found", "text/plain", "not found".to_string())
+            };
+
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                content_type,
+                body.len(),
+                body
+            );
+            let _ = stream.write_all(response.as_bytes()).await;
+        });
+    }
+}
+
+/// POST `payload` to `work_notify_settings.notify_url` (if configured), so
+/// external/secondary miners watching for pushes don't need to poll `/work`
+/// on their own. Called by `update_next_block` whenever it installs a new
+/// chain tip.
+async fn notify_work(server: &Server, payload: &work_notify::WorkPayload) {
+    let notify_url = match &server.work_notify_settings.notify_url {
+        Some(url) => url.clone(),
+        None => return,
+    };
+    if let Err(err) = server.client.post(&notify_url).json(payload).send().await {
+        server.log().warn(format!("Failed to deliver work-notify POST: {}", err), Some("WorkNotify"));
     }
 }
 
 async fn init_request(server: &Server) -> RequestBuilder {
     let node_settings = server.node_settings.lock().await;
-    server.client.post(&node_settings.bitcoind_url).basic_auth(
-        &node_settings.bitcoind_user,
-        Some(&node_settings.bitcoind_password),
-    )
+    server
+        .client
+        .post(node_settings.active_url())
+        .basic_auth(&node_settings.bitcoind_user, Some(&node_settings.bitcoind_password))
+        .timeout(node_settings.rpc_request_timeout)
 }
 
 fn display_hash(hash: &[u8]) -> String {
@@ -237,13 +831,51 @@ fn display_hash(hash: &[u8]) -> String {
     hex::encode(&hash)
 }
 
+/// Run `fut` with a deadline of `server.task_timeout` (see
+/// `ConfigSettings::task_timeout`), logging a distinct warning and
+/// incrementing `Statistics::record_timeout` if it's exceeded, instead of
+/// letting a stalled node/pool connection hang the caller's loop forever.
+/// `label` identifies the call in the log line (e.g. `"update_next_block"`).
+async fn with_task_timeout<T>(server: &Server, label: &str, fut: impl std::future::Future<Output = T>) -> Option<T> {
+    match tokio::time::timeout(server.task_timeout, fut).await {
+        Ok(value) => Some(value),
+        Err(_) => {
+            server.log().warn(
+                format!("⏱️ {} timed out after {}s; treating as a hung connection", label, server.task_timeout.as_secs()),
+                Some("Miner"),
+            );
+            server.statistics.record_timeout();
+            None
+        }
+    }
+}
+
 async fn update_next_block(server: &Server) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let log = server.log();
-    let url = server.node_settings.lock().await.bitcoind_url.clone();
-    
+
+    // Give a failed-over primary endpoint another chance every so often, so
+    // the miner self-heals once the primary node comes back.
+    {
+        let mut node_settings = server.node_settings.lock().await;
+        if node_settings.active_endpoint != 0 {
+            let mut last_retry = server.last_primary_retry.lock().await;
+            if last_retry.elapsed().unwrap_or(Duration::ZERO) > PRIMARY_RETRY_INTERVAL {
+                log.info(format!("🔁 Retrying primary RPC endpoint {}", node_settings.bitcoind_urls[0]), Some("RPC"));
+                node_settings.active_endpoint = 0;
+                // One failure away from failing over again, so a still-dead
+                // primary doesn't get `MAX_CONSECUTIVE_RPC_ERRORS` tries before
+                // we fall back to the endpoint that was actually working.
+                node_settings.consecutive_rpc_errors = MAX_CONSECUTIVE_RPC_ERRORS - 1;
+                *last_retry = SystemTime::now();
+            }
+        }
+    }
+
+    let url = server.node_settings.lock().await.active_url().to_string();
+
     let request_start = std::time::Instant::now();
     log.debug(format!("🛰️ [DEBUG] RPC call: getrawunsolvedblock to URL: {}", url), Some("RPC"));
-    
+
     let request_body = {
         let miner_addr = server.node_settings.lock().await.miner_addr.clone();
         format!(
@@ -251,18 +883,23 @@ async fn update_next_block(server: &Server) -> Result<(), Box<dyn std::error::Er
             miner_addr
         )
     };
-    
-    let response = init_request(&server)
-        .await
-        .body(request_body)
-        .send()
-        .await?;
-        
+
+    let response = match init_request(&server).await.body(request_body).send().await {
+        Ok(response) => response,
+        Err(err) => {
+            log.error(format!("RPC request to {} failed: {}", url, err), Some("RPC"));
+            server.node_settings.lock().await.record_rpc_failure(log);
+            return Ok(());
+        }
+    };
+    server.node_settings.lock().await.record_rpc_success();
+
     let status = response.status();
     let network_time = request_start.elapsed();
-    log.debug(format!("🛰️ [DEBUG] RPC response status: {} for getrawunsolvedblock (took: {}ms)", 
+    *server.last_rpc_poll_latency.lock().await = network_time;
+    log.debug(format!("🛰️ [DEBUG] RPC response status: {} for getrawunsolvedblock (took: {}ms)",
         status, network_time.as_millis()), Some("RPC"));
-    
+
     let response_str = response.text().await?;
     log.debug(format!("🛰️ [DEBUG] RPC response body length: {} characters", response_str.len()), Some("RPC"));
     
@@ -299,30 +936,73 @@ async fn update_next_block(server: &Server) -> Result<(), Box<dyn std::error::Er
     let total_time = request_start.elapsed();
     
     let mut block_state = server.block_state.lock().await;
-    
-    if let Some(current_block) = &block_state.current_block {
-        if current_block.prev_hash() != block.prev_hash() {
-            log.info(format!(
-                "🔀 Switched to new chain tip: {}",
-                display_hash(&block.prev_hash())
-            ), Some("Miner"));
-        }
+
+    let switched_tip = if let Some(current_block) = &block_state.current_block {
+        current_block.prev_hash() != block.prev_hash()
     } else {
         log.info(format!(
             "🌱 Started mining on chain tip: {}",
             display_hash(&block.prev_hash())
         ), Some("Miner"));
+        false
+    };
+    if switched_tip {
+        log.info(format!(
+            "🔀 Switched to new chain tip: {}",
+            display_hash(&block.prev_hash())
+        ), Some("Miner"));
     }
-    
+
     block_state.extra_nonce += 1;
+    let new_tip_payload = switched_tip.then(|| work_notify::WorkPayload {
+        header: hex::encode(block.header),
+        body: hex::encode(&block.body),
+        target: hex::encode(block.target),
+        height: block.height(),
+        extra_nonce: block_state.extra_nonce,
+    });
     block_state.next_block = Some(block);
-    
-    log.debug(format!("🛰️ Work fetch completed in {}ms (network: {}ms)", 
+    drop(block_state);
+
+    if let Some(payload) = new_tip_payload {
+        notify_work(server, &payload).await;
+    }
+
+    log.debug(format!("🛰️ Work fetch completed in {}ms (network: {}ms)",
         total_time.as_millis(), network_time.as_millis()), Some("RPC"));
-        
+
     Ok(())
 }
 
+/// POST a JSON payload to `notify_webhook_url` (if configured) when a share
+/// or block is accepted, so solo miners can wire up Discord/Slack/HTTP
+/// alerts without tailing logs.
+async fn notify_webhook(server: &Server, block: &Block) {
+    let webhook_url = match &server.notify_webhook_url {
+        Some(url) => url.clone(),
+        None => return,
+    };
+    let miner_addr = server.node_settings.lock().await.miner_addr.clone();
+    let gpu_indices: Vec<usize> = server
+        .miners()
+        .iter()
+        .flat_map(|miner| miner.lock().unwrap().gpu_indices().to_vec())
+        .collect();
+
+    let payload = serde_json::json!({
+        "username": server.notify_webhook_username,
+        "height": block.height(),
+        "hash": hex::encode(block.hash()),
+        "target": hex::encode(block.target),
+        "mine_to_address": miner_addr,
+        "gpu_indices": gpu_indices,
+    });
+
+    if let Err(err) = server.client.post(&webhook_url).json(&payload).send().await {
+        server.log().warn(format!("Failed to deliver webhook notification: {}", err), Some("Webhook"));
+    }
+}
+
 async fn submit_block(server: &Server, block: &Block) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     #[derive(Deserialize)]
     struct SubmitBlockResponse {
@@ -333,14 +1013,15 @@ async fn submit_block(server: &Server, block: &Block) -> Result<(), Box<dyn std:
     let mut serialized_block = block.header.to_vec();
     serialized_block.extend_from_slice(&block.body);
     
-    let (url, user, password, pool_mining, miner_addr) = {
+    let (url, user, password, pool_mining, miner_addr, request_timeout) = {
         let node_settings = server.node_settings.lock().await;
         (
-            node_settings.bitcoind_url.clone(),
-            node_settings.bitcoind_user.clone(), 
+            node_settings.active_url().to_string(),
+            node_settings.bitcoind_user.clone(),
             node_settings.bitcoind_password.clone(),
             node_settings.pool_mining,
-            node_settings.miner_addr.clone()
+            node_settings.miner_addr.clone(),
+            node_settings.rpc_request_timeout,
         )
     };
     log.info(format!("🛰️ Submitting share to pool: {}", url), Some("RPC"));
@@ -360,13 +1041,30 @@ async fn submit_block(server: &Server, block: &Block) -> Result<(), Box<dyn std:
     };
     
     log.debug(format!("🛰️ [DEBUG] RPC call: submitblock to URL: {}", url), Some("RPC"));
-    
-    let response = server.client.post(&url)
-        .basic_auth(user, Some(password))
-        .header("Content-Type", "application/json")
-        .body(request_body)
-        .send()
-        .await?;
+
+    let mut attempt = 0;
+    let response = loop {
+        let result = server.client.post(&url)
+            .basic_auth(&user, Some(&password))
+            .header("Content-Type", "application/json")
+            .timeout(request_timeout)
+            .body(request_body.clone())
+            .send()
+            .await;
+        match result {
+            Ok(response) => break response,
+            Err(err) if attempt < MAX_SUBMIT_RETRIES => {
+                let delay = rpc_backoff_delay(attempt, &mut server.rng.lock().await);
+                log.debug(format!(
+                    "🔁 submitblock to {} failed ({}); retrying in {}ms (attempt {}/{})",
+                    url, err, delay.as_millis(), attempt + 1, MAX_SUBMIT_RETRIES
+                ), Some("RPC"));
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            Err(err) => return Err(Box::new(err)),
+        }
+    };
     log.debug(format!("🛰️ [DEBUG] RPC response status: {} for submitblock", response.status()), Some("RPC"));
     let status = response.status();
     let response_text = response.text().await?;
@@ -374,64 +1072,63 @@ async fn submit_block(server: &Server, block: &Block) -> Result<(), Box<dyn std:
     log.debug(format!("🛰️ [DEBUG] RPC response body length: {} characters for submitblock", response_text.len()), Some("RPC"));
     
     let response: Result<SubmitBlockResponse, _> = serde_json::from_str(&response_text);
-    
+
     match response {
         Ok(parsed) => {
-            match parsed.result {
-                None => {
-                    if let Some(error) = parsed.error {
-                        log.error(format!("REJECTED BLOCK: Error {:?}", error), Some("Share"));
-                        log.error("Something is misconfigured; make sure you run the latest lotusd/Lotus-QT and lotus-gpu-miner.", Some("Share"));
+            let outcome = rpc::classify_submit_response(parsed.result.as_deref(), parsed.error.as_ref());
+            match outcome {
+                rpc::SubmitOutcome::Accepted => {
+                    server.statistics.record_share(statistics::ShareOutcome::Accepted);
+                    notify_webhook(server, block).await;
+                    if pool_mining {
+                        log.info(
+                            format!(
+                                "🎉 Share accepted by \"{}\" for \"{}\" !",
+                                url, miner_addr
+                            ),
+                            Some("Share")
+                        );
                     } else {
-                        if pool_mining {
-                            log.info(
-                                format!(
-                                    "🎉 Share accepted by \"{}\" for \"{}\" !",
-                                    url, miner_addr
-                                ),
-                                Some("Share")
-                            );
-                        } else {
-                            log.info("🎉 Block accepted!", Some("Share"));
-                        }
+                        log.info("🎉 Block accepted!", Some("Share"));
                     }
-                },
-                Some(reason) => {
-                    if reason.is_empty() {
-                        if pool_mining {
-                            log.info(
-                                format!(
-                                    "🎉 Share accepted by \"{}\" for \"{}\" !",
-                                    url, miner_addr
-                                ),
-                                Some("Share")
-                            );
-                        } else {
-                            log.info("🎉 Block accepted!", Some("Share"));
-                        }
+                }
+                rpc::SubmitOutcome::OrphanRace => {
+                    server.statistics.record_share(statistics::ShareOutcome::Stale);
+                    if pool_mining {
+                        log.error("REJECTED SHARE: inconclusive", Some("Share"));
                     } else {
-                        if pool_mining {
-                            log.error(format!("REJECTED SHARE: {}", reason), Some("Share"));
-                        } else {
-                            log.error(format!("REJECTED BLOCK: {}", reason), Some("Share"));
-                        }
-                        if reason == "inconclusive" {
-                            log.warn(
-                                "This is an orphan race; might be fixed by lowering rpc_poll_interval or \
-                                updating to the newest lotus-gpu-miner.", Some("Share")
-                            );
-                        } else {
-                            log.error(
-                                "Something is misconfigured; make sure you run the latest \
-                                lotusd/Lotus-QT and lotus-gpu-miner.", Some("Share")
-                            );
-                        }
+                        log.error("REJECTED BLOCK: inconclusive", Some("Share"));
                     }
+                    log.warn(
+                        "This is an orphan race; might be fixed by lowering rpc_poll_interval or \
+                        updating to the newest lotus-gpu-miner.", Some("Share")
+                    );
+                    // Don't wait for the next scheduled poll; the tip has
+                    // already moved on, so fetch the new one right away
+                    // instead of continuing to search the stale job.
+                    if let Some(Err(err)) = with_task_timeout(server, "update_next_block", update_next_block(server)).await {
+                        log.error(format!("Failed to refresh work after orphan race: {}", err), Some("RPC"));
+                    }
+                }
+                rpc::SubmitOutcome::Rejected { code, message } => {
+                    server.statistics.record_rejection(message.clone());
+                    if pool_mining {
+                        log.error(format!("REJECTED SHARE: {}", message), Some("Share"));
+                    } else {
+                        log.error(format!("REJECTED BLOCK: {}", message), Some("Share"));
+                    }
+                    if let Some(code) = code {
+                        log.debug(format!("RPC error code: {}", code), Some("RPC"));
+                    }
+                    log.error(
+                        "Something is misconfigured; make sure you run the latest \
+                        lotusd/Lotus-QT and lotus-gpu-miner.", Some("Share")
+                    );
                 }
             }
         },
         Err(e) => {
-            log.error(format!("Failed to parse response: {} (Status: {})\nResponse: {}", 
+            log.error(format!("Failed to parse response: {} (Status: {})\nResponse: {}",
                 e, status, response_text), Some("Miner"));
         }
     }
@@ -441,6 +1138,312 @@ async fn submit_block(server: &Server, block: &Block) -> Result<(), Box<dyn std:
     Ok(())
 }
 
+/// Search for a winning nonce across every configured GPU in parallel: this
+/// is the worker pool, one `spawn_blocking` task per device each owning its
+/// own `Miner` and disjoint nonce sub-range, dispatched fresh every time the
+/// caller's poll loop comes back around (rather than a persistent thread
+/// parked on a `Condvar`, which the rest of this codebase's poll-loop style
+/// doesn't otherwise use).
+///
+/// The 64-bit nonce space is split into one contiguous partition per device
+/// (ethminer's "Farm" approach of handing each card a disjoint range) so
+/// multiple cards never redo each other's work. Returns the first winning
+/// nonce found (if any) and the total nonces searched by the devices that
+/// had reported by then (every device, when no nonce is found);
+/// per-device totals are folded into `server.device_nonces` for the
+/// hashrate breakdown in the periodic report.
+/// The first device to report a qualifying nonce doesn't wait on the
+/// others: OpenCL kernels aren't abortable mid-batch, so stragglers are
+/// left running and drained into `server.device_nonces`/`Statistics` by a
+/// detached task instead of holding up submission until the slowest
+/// device's batch completes.
+async fn mine_across_devices(server: &ServerRef, work: &Work) -> (Option<(u64, bool)>, u64) {
+    let num_devices = server.miners.len().max(1);
+    let nonce_span = u64::MAX / num_devices as u64;
+
+    let mut tasks = tokio::task::JoinSet::new();
+    for device_idx in 0..server.miners.len() {
+        let server = Arc::clone(server);
+        let mut device_work = *work;
+        let base_nonce = nonce_span.saturating_mul(device_idx as u64);
+        let random_offset: u64 = server.rng.lock().await.gen_range(0..nonce_span.max(1));
+        device_work.set_big_nonce(base_nonce.saturating_add(random_offset));
+
+        tasks.spawn_blocking(move || {
+            let log = server.log();
+            let mut miner = server.miners[device_idx].lock().unwrap();
+            if !miner.has_nonces_left(&device_work) {
+                log.error("Error: Exhaustively searched nonces", Some("Miner"));
+                return (device_idx, None, 0);
+            }
+            let (nonce, num_nonces) = miner
+                .find_nonce(&device_work, log)
+                .map(|nonce| (nonce, miner.num_nonces_per_search()))
+                .unwrap_or((None, 0));
+            (device_idx, nonce, num_nonces)
+        });
+    }
+
+    let mut winning_nonce = None;
+    let mut total_nonces = 0u64;
+    while let Some(result) = tasks.join_next().await {
+        if let Ok((device_idx, nonce, num_nonces)) = result {
+            total_nonces += num_nonces;
+            server.device_nonces[device_idx].fetch_add(num_nonces, Ordering::AcqRel);
+            if nonce.is_some() {
+                winning_nonce = nonce;
+                break;
+            }
+        }
+    }
+
+    if !tasks.is_empty() {
+        let server = Arc::clone(server);
+        tokio::spawn(async move {
+            while let Some(result) = tasks.join_next().await {
+                if let Ok((device_idx, _nonce, num_nonces)) = result {
+                    server.device_nonces[device_idx].fetch_add(num_nonces, Ordering::AcqRel);
+                    server.statistics.record_nonces(num_nonces);
+                }
+            }
+        });
+    }
+
+    (winning_nonce, total_nonces)
+}
+
+/// Synchronous counterpart to `mine_across_devices`, for callers already
+/// running inside a `tokio::task::spawn_blocking` task (`stratum_dispatch_nonces`).
+/// Only the low 32 bits of the nonce space are partitioned, since Stratum
+/// v1's `mining.submit` carries an 8-hex-digit (32-bit) nonce.
+fn mine_across_devices_blocking(server: &Server, work: &Work) -> (Option<(u32, bool)>, u64) {
+    let num_devices = server.miners.len().max(1);
+    let nonce_span = (u32::MAX as u64) / num_devices as u64;
+
+    let winner: std::sync::Mutex<Option<(u32, bool)>> = std::sync::Mutex::new(None);
+    let total_nonces = AtomicU64::new(0);
+
+    std::thread::scope(|scope| {
+        for device_idx in 0..server.miners.len() {
+            let winner = &winner;
+            let total_nonces = &total_nonces;
+            let base_nonce = nonce_span.saturating_mul(device_idx as u64);
+            // Re-randomize the start offset within the device's partition on
+            // every dispatch, matching `mine_across_devices`; otherwise a
+            // still-unsolved job would have each device rescan the exact same
+            // window every time this is called instead of covering fresh
+            // nonces, wasting most of the device's contribution.
+            let random_offset: u64 = server.rng.blocking_lock().gen_range(0..nonce_span.max(1));
+            let mut device_work = *work;
+            device_work.set_big_nonce(base_nonce.saturating_add(random_offset));
+
+            scope.spawn(move || {
+                let log = server.log();
+                let mut miner = server.miners[device_idx].lock().unwrap();
+                if !miner.has_nonces_left(&device_work) {
+                    log.error("Error: Exhaustively searched nonces", Some("Miner"));
+                    return;
+                }
+                if let Ok(Some((nonce, is_block))) = miner.find_nonce(&device_work, log) {
+                    *winner.lock().unwrap() = Some((nonce as u32, is_block));
+                }
+                let num_nonces = miner.num_nonces_per_search();
+                total_nonces.fetch_add(num_nonces, Ordering::AcqRel);
+                server.device_nonces[device_idx].fetch_add(num_nonces, Ordering::AcqRel);
+            });
+        }
+    });
+
+    (*winner.lock().unwrap(), total_nonces.load(Ordering::Acquire))
+}
+
+/// Own the persistent Stratum connection: complete the handshake, publish it
+/// into `server.stratum_client`, then loop handling pool pushes until the
+/// connection drops, at which point the caller (`Server::run`) reconnects.
+/// `stratum_dispatch_nonces` reaches into the same connection only to submit
+/// a found nonce, since the socket can't be split across the two tasks.
+async fn stratum_controller(server: ServerRef, addr: String) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let (worker, password) = (server.stratum_worker.clone(), server.stratum_password.clone());
+    server.log().info(format!("⛏️ Connecting to Stratum pool at {}", addr), Some("Stratum"));
+
+    let rt = tokio::runtime::Handle::current();
+    let result = {
+        let server = Arc::clone(&server);
+        tokio::task::spawn_blocking(move || stratum_controller_loop(server, addr, worker, password, rt)).await?
+    };
+    *server.stratum_client.lock().unwrap() = None;
+    result
+}
+
+/// Blocking body of `stratum_controller`. Handled pushes mirror the old
+/// single-loop client: `mining.notify` jobs are rebuilt into `block_state`
+/// via `load_stratum_job`, `mining.set_difficulty` updates `share_target`,
+/// and delayed `mining.submit` replies update the accepted/rejected counters.
+fn stratum_controller_loop(
+    server: ServerRef,
+    addr: String,
+    worker: String,
+    password: String,
+    rt: tokio::runtime::Handle,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let log = server.log();
+    let client = stratum::StratumClient::connect(&addr, &worker, &password)?;
+    log.info(format!("✅ Subscribed to {} as \"{}\"", addr, worker), Some("Stratum"));
+    *server.stratum_client.lock().unwrap() = Some(client);
+
+    loop {
+        let event = {
+            let mut guard = server.stratum_client.lock().unwrap();
+            guard.as_mut().expect("set above, only cleared by the caller on reconnect").try_next_event()?
+        };
+
+        let event = match event {
+            Some(event) => event,
+            None => continue,
+        };
+
+        match event {
+            stratum::StratumEvent::SetDifficulty(difficulty) => {
+                rt.block_on(async {
+                    *server.share_target.lock().await = pow::difficulty_to_target(difficulty);
+                });
+                log.info(format!("🎯 Pool set share difficulty to {}", difficulty), Some("Stratum"));
+            }
+            stratum::StratumEvent::SubmitResult { accepted, code, detail } => {
+                if accepted {
+                    server.statistics.record_share(statistics::ShareOutcome::Accepted);
+                    log.info("🎉 Share accepted by pool!", Some("Share"));
+                } else if stratum::is_stale_error_code(code) {
+                    // The pool already moved on to a new job; not a sign of
+                    // a miner bug. A fresh `mining.notify` is on its way (the
+                    // pool pushes jobs unprompted, unlike the RPC poll loop),
+                    // so there's nothing to request here, just count it.
+                    let reason = detail.unwrap_or_default();
+                    server.statistics.record_share(statistics::ShareOutcome::Stale);
+                    log.error(format!("REJECTED SHARE: {} (stale job)", reason), Some("Share"));
+                } else {
+                    let reason = detail.unwrap_or_default();
+                    server.statistics.record_rejection(reason.clone());
+                    log.error(format!("REJECTED SHARE: {}", reason), Some("Share"));
+                    log.error(
+                        "This share was rejected as invalid, not stale; if this keeps \
+                        happening it likely indicates a miner bug.", Some("Share")
+                    );
+                }
+            }
+            stratum::StratumEvent::Job(job) => {
+                log.info(format!("📝 New job {} (version {:08x}, n_bits {:08x})", job.job_id, job.version, job.n_bits), Some("Stratum"));
+                rt.block_on(load_stratum_job(&server, &job));
+            }
+        }
+    }
+}
+
+/// Rebuild a `mining.notify` job's header into a `Work`/`Block` pair and load
+/// it into `block_state`, mirroring `update_next_block`'s role for the RPC
+/// path. A `clean_jobs` push replaces `current_block`/`current_work`
+/// immediately (instead of just queuing into `next_block`) and resets
+/// `nonce_idx`, since the pool is telling us any nonce found against the old
+/// job is now stale.
+async fn load_stratum_job(server: &Server, job: &stratum::StratumJob) {
+    let share_target = *server.share_target.lock().await;
+    let mut block_state = server.block_state.lock().await;
+
+    block_state.extra_nonce += 1;
+    let extranonce2_size = server
+        .stratum_client
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|client| client.extranonce2_size.min(8))
+        .unwrap_or(4);
+    let extranonce2 = block_state.extra_nonce.to_le_bytes()[..extranonce2_size].to_vec();
+    let header = {
+        let guard = server.stratum_client.lock().unwrap();
+        stratum::build_job_header(guard.as_ref().expect("set by stratum_controller_loop"), job, &extranonce2).0
+    };
+
+    let block_target = pow::nbits_to_target(job.n_bits);
+    let block = Block { header, body: Vec::new(), target: share_target };
+    let stratum_job = StratumJobContext {
+        job_id: job.job_id.clone(),
+        extranonce2,
+        n_time: job.n_time,
+        block_target,
+    };
+
+    if job.clean_jobs {
+        server.log().debug("🧹 Pool requested clean_jobs, replacing current work immediately", Some("Stratum"));
+        block_state.current_block = Some(block.clone());
+        block_state.current_work = Work::from_header(header, share_target).with_block_target(block_target);
+        block_state.current_work.nonce_idx = 0;
+    }
+    block_state.next_block = Some(block);
+    block_state.stratum_job = Some(stratum_job);
+}
+
+/// Mine the currently loaded Stratum job across every GPU and submit any
+/// winning nonce, mirroring `mine_some_nonces`'s role for the RPC path but
+/// driven by jobs `stratum_controller` pushes into `block_state` instead of
+/// an RPC poll.
+async fn stratum_dispatch_nonces(server: ServerRef) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let (work, stratum_job) = {
+        let mut block_state = server.block_state.lock().await;
+        if let Some(next_block) = block_state.next_block.take() {
+            // `stratum_job` is always set in the same `load_stratum_job` call
+            // that sets `next_block`, so it already holds this job's real
+            // block target - `next_block.target` itself is only the (much
+            // easier) share target.
+            let block_target = block_state
+                .stratum_job
+                .as_ref()
+                .map(|job| job.block_target)
+                .unwrap_or(next_block.target);
+            block_state.current_work =
+                Work::from_header(next_block.header, next_block.target).with_block_target(block_target);
+            block_state.current_block = Some(next_block);
+        }
+        let stratum_job = match (&block_state.current_block, &block_state.stratum_job) {
+            (Some(_), Some(job)) => job.clone(),
+            _ => return Ok(()),
+        };
+        if block_state.current_work.nonce_idx > 1000 {
+            block_state.current_work.nonce_idx = 0;
+        }
+        (block_state.current_work.clone(), stratum_job)
+    };
+
+    let server_for_mining = Arc::clone(&server);
+    let (nonce, num_nonces) =
+        tokio::task::spawn_blocking(move || mine_across_devices_blocking(&server_for_mining, &work)).await?;
+    server.statistics.record_nonces(num_nonces);
+
+    {
+        let mut block_state = server.block_state.lock().await;
+        block_state.current_work.nonce_idx += 1;
+    }
+
+    if let Some((nonce, is_block)) = nonce {
+        server.log().info(format!("💎 Share found for job {} at nonce {}", stratum_job.job_id, nonce), Some("Share"));
+        if is_block {
+            server.log().info(format!("This share for job {} also solves the block!", stratum_job.job_id), Some("Share"));
+        }
+        let worker = server.stratum_worker.clone();
+        let server_for_submit = Arc::clone(&server);
+        tokio::task::spawn_blocking(move || {
+            let mut guard = server_for_submit.stratum_client.lock().unwrap();
+            if let Some(client) = guard.as_mut() {
+                if let Err(err) = client.submit(&worker, &stratum_job.job_id, &stratum_job.extranonce2, stratum_job.n_time, nonce) {
+                    server_for_submit.log().error(format!("Failed to submit share: {:?}", err), Some("Stratum"));
+                }
+            }
+        })
+        .await?;
+    }
+
+    Ok(())
+}
+
 async fn mine_some_nonces(server: ServerRef) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let log = server.log();
     let pool_mining = server.node_settings.lock().await.pool_mining;
@@ -452,7 +1455,7 @@ async fn mine_some_nonces(server: ServerRef) -> Result<(), Box<dyn std::error::E
         let block_state = server.block_state.lock().await;
         if block_state.next_block.is_none() && block_state.current_block.is_none() {
             drop(block_state);
-            if let Err(err) = update_next_block(&server).await {
+            if let Some(Err(err)) = with_task_timeout(&server, "update_next_block", update_next_block(&server)).await {
                 log.error(format!("Failed to initialize first block: {:?}", err), Some("Miner"));
                 tokio::time::sleep(Duration::from_millis(100)).await;
             }
@@ -477,14 +1480,21 @@ async fn mine_some_nonces(server: ServerRef) -> Result<(), Box<dyn std::error::E
                         };
                         
                         if needs_work {
-                            if let Err(err) = update_next_block(&inner_server).await {
-                                log.error(format!("Failed to prefetch next block: {:?}", err), Some("Miner"));
-                                tokio::time::sleep(Duration::from_millis(50)).await;
-                            } else {
-                                log.debug("✅ Successfully prefetched next work", Some("Miner"));
+                            match with_task_timeout(&inner_server, "update_next_block prefetch", update_next_block(&inner_server)).await {
+                                Some(Ok(())) => log.debug("✅ Successfully prefetched next work", Some("Miner")),
+                                Some(Err(err)) => {
+                                    log.error(format!("Failed to prefetch next block: {:?}", err), Some("Miner"));
+                                    tokio::time::sleep(Duration::from_millis(50)).await;
+                                }
+                                None => {
+                                    // Timed out; loop straight back around
+                                    // for a fresh attempt instead of waiting
+                                    // out the usual poll delay below.
+                                    continue;
+                                }
                             }
                         }
-                        
+
                         tokio::time::sleep(Duration::from_millis(5)).await;
                     }
                 }
@@ -511,10 +1521,14 @@ async fn mine_some_nonces(server: ServerRef) -> Result<(), Box<dyn std::error::E
                     
                     if !has_next_block {
                         log.info("⚠️ Prefetch not completed in time, fetching directly", Some("Miner"));
-                        if let Err(err) = update_next_block(&server_clone).await {
-                            log.error(format!("Failed to fetch work: {:?}", err), Some("Miner"));
-                            tokio::time::sleep(Duration::from_millis(100)).await;
-                            continue;
+                        match with_task_timeout(&server_clone, "update_next_block", update_next_block(&server_clone)).await {
+                            Some(Ok(())) => {}
+                            Some(Err(err)) => {
+                                log.error(format!("Failed to fetch work: {:?}", err), Some("Miner"));
+                                tokio::time::sleep(Duration::from_millis(100)).await;
+                                continue;
+                            }
+                            None => continue,
                         }
                     }
                 }
@@ -576,56 +1590,27 @@ async fn mine_some_nonces(server: ServerRef) -> Result<(), Box<dyn std::error::E
                     }
                 }
                 
-                let big_nonce = server_clone.rng.lock().await.gen();
-                work.set_big_nonce(big_nonce);
-                
                 let start_time = std::time::Instant::now();
-                log.debug(format!("⚡ Starting mining with nonce base {}", big_nonce), Some("Miner"));
-                
-                let mining_result = tokio::task::spawn_blocking({
-                    let inner_server = Arc::clone(&server_clone);
-                    move || {
-                        let log = inner_server.log();
-                        let mut miner = inner_server.miner.lock().unwrap();
-                        if !miner.has_nonces_left(&work) {
-                            log.error("Error: Exhaustively searched nonces", Some("Miner"));
-                            return Ok((None, 0));
-                        }
-                        miner
-                            .find_nonce(&work, inner_server.log())
-                            .map(|nonce| (nonce, miner.num_nonces_per_search()))
-                    }
-                })
-                .await;
-                
+                log.debug("⚡ Starting mining across all configured GPUs", Some("Miner"));
+
+                let (nonce, num_nonces_per_search) = mine_across_devices(&server_clone, &work).await;
+
                 let mining_duration = start_time.elapsed();
-                log.debug(format!("✅ Mining batch completed in {}ms", 
+                log.debug(format!("✅ Mining batch completed in {}ms",
                     mining_duration.as_millis()), Some("Miner"));
                 
-                let (nonce, num_nonces_per_search) = match mining_result {
-                    Ok(Ok((nonce, num_nonces))) => (nonce, num_nonces),
-                    Ok(Err(err)) => {
-                        log.error(format!("Mining error: {:?}", err), Some("Miner"));
-                        (None, 0)
-                    },
-                    Err(err) => {
-                        log.error(format!("Task join error: {:?}", err), Some("Miner"));
-                        (None, 0)
-                    }
-                };
-                
-                if let Some(nonce) = nonce {
+                if let Some((nonce, _is_block)) = nonce {
                     work.set_big_nonce(nonce);
                     log.info(format!("💎 Block hash below target with nonce: {}", nonce), Some("Share"));
-                    
+
                     let fetch_server = Arc::clone(&server_clone);
                     tokio::spawn(async move {
                         let log = fetch_server.log();
                         log.info("⚡ Share found, fetching fresh work in parallel with submission...", Some("Miner"));
-                        if let Err(err) = update_next_block(&fetch_server).await {
-                            log.error(format!("Failed to update next block after share: {:?}", err), Some("Miner"));
-                        } else {
-                            log.debug("✅ Successfully fetched new work after share", Some("Miner"));
+                        match with_task_timeout(&fetch_server, "update_next_block", update_next_block(&fetch_server)).await {
+                            Some(Ok(())) => log.debug("✅ Successfully fetched new work after share", Some("Miner")),
+                            Some(Err(err)) => log.error(format!("Failed to update next block after share: {:?}", err), Some("Miner")),
+                            None => {}
                         }
                     });
                     
@@ -644,7 +1629,7 @@ async fn mine_some_nonces(server: ServerRef) -> Result<(), Box<dyn std::error::E
                         let submit_server = Arc::clone(&server_clone);
                         tokio::spawn(async move {
                             let log = submit_server.log();
-                            if let Err(err) = submit_block(&submit_server, &block).await {
+                            if let Some(Err(err)) = with_task_timeout(&submit_server, "submit_block", submit_block(&submit_server, &block)).await {
                                 log.error(format!(
                                     "submit_block error: {:?}. This could be a connection issue.",
                                     err
@@ -659,27 +1644,9 @@ async fn mine_some_nonces(server: ServerRef) -> Result<(), Box<dyn std::error::E
                     // Update statistics even when no nonce is found
                     let mut block_state = server_clone.block_state.lock().await;
                     block_state.current_work.nonce_idx += 1;
-                    server_clone.metrics_nonces.fetch_add(num_nonces_per_search, Ordering::AcqRel);
+                    server_clone.statistics.record_nonces(num_nonces_per_search);
                 }
-                
-                // Report hashrate if needed
-                {
-                    let mut timestamp = server_clone.metrics_timestamp.lock().await;
-                    let elapsed = match SystemTime::now().duration_since(*timestamp) {
-                        Ok(elapsed) => elapsed,
-                        Err(err) => {
-                            log.bug(format!("Bug: Elapsed time error: {}. Contact the developers.", err), Some("Miner"));
-                            continue;
-                        }
-                    };
-                    
-                    if elapsed > server_clone.report_hashrate_interval {
-                        let hashrate = server_clone.calculate_moving_average_hashrate().await;
-                        log.report_hashrate(hashrate);
-                        *timestamp = SystemTime::now();
-                    }
-                }
-                
+
                 // Next iteration will immediately get the already prefetched work
                 // This creates a zero-wait mining cycle
             }
@@ -719,7 +1686,9 @@ async fn mine_some_nonces(server: ServerRef) -> Result<(), Box<dyn std::error::E
                     // No current block, so we need to get one
                     drop(block_state);
                     log.info("⏳ No work available, fetching immediately...", Some("Miner"));
-                    if let Err(err) = update_next_block(&server).await {
+                    if let Some(Err(err)) =
+                        with_task_timeout(&server, "update_next_block", update_next_block(&server)).await
+                    {
                         log.error(format!("Failed to update next block: {:?}", err), Some("Miner"));
                         tokio::time::sleep(Duration::from_millis(100)).await;
                     }
@@ -736,7 +1705,9 @@ async fn mine_some_nonces(server: ServerRef) -> Result<(), Box<dyn std::error::E
                 tokio::spawn(async move {
                     let log = server_clone.log();
                     log.debug("🔄 Proactively prefetching next work while mining current block", Some("Miner"));
-                    if let Err(err) = update_next_block(&server_clone).await {
+                    if let Some(Err(err)) =
+                        with_task_timeout(&server_clone, "update_next_block", update_next_block(&server_clone)).await
+                    {
                         log.error(format!("Proactive prefetch failed: {:?}", err), Some("Miner"));
                     }
                 });
@@ -757,44 +1728,11 @@ async fn mine_some_nonces(server: ServerRef) -> Result<(), Box<dyn std::error::E
                 }
             }
             
-            let big_nonce = server.rng.lock().await.gen();
-            work.set_big_nonce(big_nonce);
-            
-            // Run the mining operation on the GPU
-            let mining_result = tokio::task::spawn_blocking({
-                let server = Arc::clone(&server);
-                move || {
-                    let log = server.log();
-                    let mut miner = server.miner.lock().unwrap();
-                    if !miner.has_nonces_left(&work) {
-                        log.error(format!(
-                            "Error: Exhaustively searched nonces. This could be fixed by lowering \
-                                   rpc_poll_interval."
-                        ), Some("Miner"));
-                        return Ok((None, 0));
-                    }
-                    miner
-                        .find_nonce(&work, server.log())
-                        .map(|nonce| (nonce, miner.num_nonces_per_search()))
-                }
-            })
-            .await;
-            
-            // Handle the mining result
-            let (nonce, num_nonces_per_search) = match mining_result {
-                Ok(Ok((nonce, num_nonces))) => (nonce, num_nonces),
-                Ok(Err(err)) => {
-                    log.error(format!("Mining error: {:?}", err), Some("Miner"));
-                    (None, 0)
-                },
-                Err(err) => {
-                    log.error(format!("Task join error: {:?}", err), Some("Miner"));
-                    (None, 0)
-                }
-            };
+            // Run the mining operation across every configured GPU
+            let (nonce, num_nonces_per_search) = mine_across_devices(&server, &work).await;
             
             // Handle found nonce (share/block)
-            if let Some(nonce) = nonce {
+            if let Some((nonce, _is_block)) = nonce {
                 work.set_big_nonce(nonce);
                 log.info(format!("💎 Block hash below target with nonce: {}", nonce), Some("Share"));
                 
@@ -812,7 +1750,9 @@ async fn mine_some_nonces(server: ServerRef) -> Result<(), Box<dyn std::error::E
                 
                 // Submit the block/share
                 if let Some(block) = block {
-                    if let Err(err) = submit_block(&server, &block).await {
+                    if let Some(Err(err)) =
+                        with_task_timeout(&server, "submit_block", submit_block(&server, &block)).await
+                    {
                         log.error(format!(
                             "submit_block error: {:?}. This could be a connection issue.",
                             err
@@ -827,36 +1767,26 @@ async fn mine_some_nonces(server: ServerRef) -> Result<(), Box<dyn std::error::E
             {
                 let mut block_state = server.block_state.lock().await;
                 block_state.current_work.nonce_idx += 1;
-                server.metrics_nonces.fetch_add(num_nonces_per_search, Ordering::AcqRel);
+                server.statistics.record_nonces(num_nonces_per_search);
             }
-            
-            // Update and report hashrate if needed
-            {
-                let mut timestamp = server.metrics_timestamp.lock().await;
-                let elapsed = match SystemTime::now().duration_since(*timestamp) {
-                    Ok(elapsed) => elapsed,
-                    Err(err) => {
-                        log.bug(format!("Bug: Elapsed time error: {}. Contact the developers.", err), Some("Miner"));
-                        return Ok(());
-                    }
-                };
-                
-                if elapsed > server.report_hashrate_interval {
-                    let hashrate = server.calculate_moving_average_hashrate().await;
-                    log.report_hashrate(hashrate);
-                    *timestamp = SystemTime::now();
-                }
-            }
-            
+
             // For solo mining, we break here
             break;
         }
     } else {
-        // For pool mining, we don't return until the program is terminated
-        // Since the worker thread handles all the mining, just wait here
+        // For pool mining, we don't return until the program is terminated.
+        // Since the worker thread handles all the mining, just wait here,
+        // checking for an operator stop command so this task actually shuts
+        // down instead of idling forever with no way to signal it.
+        let mut worker = server.worker_registry.register("mining-pool-idle");
         loop {
+            if worker.wait_if_paused().await.is_err() {
+                break;
+            }
+            worker.progress();
             tokio::time::sleep(Duration::from_secs(1)).await;
         }
+        worker.mark_dead();
     }
     
     Ok(())
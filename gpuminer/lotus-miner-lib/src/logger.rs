@@ -1,21 +1,25 @@
 use std::{
+    collections::{HashMap, VecDeque},
     fmt::Display,
     fs::{File, OpenOptions},
-    io::{self, Write},
-    path::PathBuf,
+    io::{self, IsTerminal, Write},
+    path::{Path, PathBuf},
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc, RwLock,
+        mpsc, Arc, Condvar, Mutex, OnceLock, RwLock, Weak,
     },
+    time::Duration as StdDuration,
 };
 
 use chrono::{DateTime, Local};
 use log::{Level, LevelFilter, Log as LogTrait, Metadata, Record};
+use serde::Serialize;
 use thiserror::Error;
 use colored::*;
 
-/// Severity levels for logging, matching the log crate's levels
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Severity levels for logging, matching the log crate's levels. Ordered
+/// least to most severe so `RecordFilter::level` can be compared with `>=`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub enum LogSeverity {
     Info,
     Warn,
@@ -45,8 +49,21 @@ impl From<LogSeverity> for Level {
     }
 }
 
-/// A log entry containing a message, severity level, and timestamp
-#[derive(Debug, Clone)]
+/// What the background writer thread's queue does when it's full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelFullPolicy {
+    /// Block the producing thread until the writer catches up. Never loses
+    /// a record, at the cost of the hot path stalling if the disk does.
+    Block,
+    /// Drop the oldest queued record to make room. Guarantees the hot path
+    /// never blocks on logging, at the cost of losing records under load.
+    DropOldest,
+}
+
+/// A log entry containing a message, severity level, and timestamp.
+/// Serializes to JSON as `{tag, severity, timestamp, msg, source}`, with
+/// `timestamp` rendered as RFC3339 (chrono's default `DateTime` encoding).
+#[derive(Debug, Clone, Serialize)]
 pub struct LogRecord {
     pub msg: String,
     pub severity: LogSeverity,
@@ -57,30 +74,7 @@ pub struct LogRecord {
 
 impl Display for LogRecord {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let tag = format!("{:<9}", self.tag).bold();
-        let tag_colored = match self.tag.to_lowercase().as_str() {
-            "status" => tag.bright_cyan(),
-            "miner" => tag.bright_yellow(),
-            "opencl" => tag.bright_green(),
-            "hashrate" => tag.bright_magenta(),
-            "share" => tag.bright_blue(),
-            "shutdown" => tag.bright_red(),
-            _ => tag.white(),
-        };
-        let level_colored = match self.severity {
-            LogSeverity::Info => "Info".bright_white(),
-            LogSeverity::Warn => "Warn".yellow(),
-            LogSeverity::Error => "Error".red(),
-            LogSeverity::Bug => "Bug".magenta(),
-        };
-        write!(
-            f,
-            "[{}] [{}] [{}] {}",
-            self.timestamp.format("%Y-%m-%d %H:%M:%S%.3f"),
-            tag_colored,
-            level_colored,
-            self.msg
-        )
+        colored_formatter(self, f)
     }
 }
 
@@ -106,30 +100,7 @@ pub struct LogEntry {
 
 impl Display for LogEntry {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let tag = format!("{:<9}", self.tag).bold();
-        let tag_colored = match self.tag.to_lowercase().as_str() {
-            "status" => tag.bright_cyan(),
-            "miner" => tag.bright_yellow(),
-            "opencl" => tag.bright_green(),
-            "hashrate" => tag.bright_magenta(),
-            "share" => tag.bright_blue(),
-            "shutdown" => tag.bright_red(),
-            _ => tag.white(),
-        };
-        let level_colored = match self.severity {
-            LogSeverity::Info => "Info".bright_white(),
-            LogSeverity::Warn => "Warn".yellow(),
-            LogSeverity::Error => "Error".red(),
-            LogSeverity::Bug => "Bug".magenta(),
-        };
-        write!(
-            f,
-            "[{}] [{}] [{}] {}",
-            self.timestamp.format("%Y-%m-%d %H:%M:%S%.3f"),
-            tag_colored,
-            level_colored,
-            self.msg
-        )
+        colored_formatter(&LogRecord::from(self), f)
     }
 }
 
@@ -158,7 +129,7 @@ impl From<&LogEntry> for LogRecord {
 }
 
 /// An entry for tracking hashrate with a timestamp
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct HashrateEntry {
     pub hashrate: f64,
     pub timestamp: DateTime<Local>,
@@ -175,6 +146,109 @@ impl Display for HashrateEntry {
     }
 }
 
+/// A pluggable per-sink renderer, so the console and file sinks can each use
+/// a different layout (or none at all) instead of the fixed colored
+/// `Display` format. Stored in `LoggerConfig` and invoked from
+/// `write_log_record`.
+pub type FormatterFn = dyn Fn(&LogRecord, &mut dyn std::fmt::Write) -> std::fmt::Result + Send + Sync;
+
+#[derive(Clone)]
+pub struct Formatter(Arc<FormatterFn>);
+
+impl Formatter {
+    pub fn new(
+        f: impl Fn(&LogRecord, &mut dyn std::fmt::Write) -> std::fmt::Result + Send + Sync + 'static,
+    ) -> Self {
+        Formatter(Arc::new(f))
+    }
+
+    /// Render `record`, discarding the (practically impossible) formatting
+    /// error the same way the rest of this module discards write errors to
+    /// an in-memory `String`.
+    pub fn format(&self, record: &LogRecord) -> String {
+        let mut out = String::new();
+        let _ = (self.0)(record, &mut out);
+        out
+    }
+}
+
+impl std::fmt::Debug for Formatter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Formatter(..)")
+    }
+}
+
+/// Built-in formatter matching the historical `Display` impl: timestamp,
+/// bold tag colored by name, severity-colored level, message.
+pub fn colored_formatter(record: &LogRecord, out: &mut dyn std::fmt::Write) -> std::fmt::Result {
+    let tag = format!("{:<9}", record.tag).bold();
+    let tag_colored = match record.tag.to_lowercase().as_str() {
+        "status" => tag.bright_cyan(),
+        "miner" => tag.bright_yellow(),
+        "opencl" => tag.bright_green(),
+        "hashrate" => tag.bright_magenta(),
+        "share" => tag.bright_blue(),
+        "shutdown" => tag.bright_red(),
+        _ => tag.white(),
+    };
+    let level_colored = match record.severity {
+        LogSeverity::Info => "Info".bright_white(),
+        LogSeverity::Warn => "Warn".yellow(),
+        LogSeverity::Error => "Error".red(),
+        LogSeverity::Bug => "Bug".magenta(),
+    };
+    write!(
+        out,
+        "[{}] [{}] [{}] {}",
+        record.timestamp.format("%Y-%m-%d %H:%M:%S%.3f"),
+        tag_colored,
+        level_colored,
+        record.msg
+    )
+}
+
+/// Same layout as `colored_formatter`, minus the ANSI escape codes, for a
+/// redirected console or a log file where they'd just be noise.
+pub fn plain_formatter(record: &LogRecord, out: &mut dyn std::fmt::Write) -> std::fmt::Result {
+    write!(
+        out,
+        "[{}] [{:<9}] [{}] {}",
+        record.timestamp.format("%Y-%m-%d %H:%M:%S%.3f"),
+        record.tag,
+        level_label(record.severity),
+        record.msg
+    )
+}
+
+fn level_label(severity: LogSeverity) -> &'static str {
+    match severity {
+        LogSeverity::Info => "Info",
+        LogSeverity::Warn => "Warn",
+        LogSeverity::Error => "Error",
+        LogSeverity::Bug => "Bug",
+    }
+}
+
+/// Built-in formatter emitting `record` as a single JSON line, the same
+/// shape `file_json` already writes.
+pub fn json_formatter(record: &LogRecord, out: &mut dyn std::fmt::Write) -> std::fmt::Result {
+    match serde_json::to_string(record) {
+        Ok(line) => out.write_str(&line),
+        Err(_) => Ok(()),
+    }
+}
+
+/// `colored_formatter` when stdout is an interactive terminal, else
+/// `plain_formatter`, so redirecting the console sink to a file or a pipe
+/// doesn't leave escape codes behind.
+pub fn auto_console_formatter() -> Formatter {
+    if io::stdout().is_terminal() {
+        Formatter::new(colored_formatter)
+    } else {
+        Formatter::new(plain_formatter)
+    }
+}
+
 /// Errors that can occur during logging operations
 #[derive(Debug, Error)]
 pub enum LoggerError {
@@ -189,6 +263,9 @@ pub enum LoggerError {
 
     #[error("Failed to set logger: {0}")]
     SetLogger(String),
+
+    #[error("Invalid log filter directive: {0}")]
+    InvalidDirective(String),
 }
 
 /// Configuration for the logger
@@ -196,21 +273,66 @@ pub enum LoggerError {
 pub struct LoggerConfig {
     /// Whether to log to stdout
     pub console_output: bool,
-    
+
     /// Whether to log to a file
     pub file_output: bool,
-    
+
     /// Path to the log file (if file_output is true)
     pub log_file_path: Option<PathBuf>,
-    
+
+    /// Write file-sink records as JSON lines (one `LogRecord` per line)
+    /// instead of rendering them with `file_formatter`.
+    pub file_json: bool,
+
+    /// Renderer for the console sink. Defaults to `auto_console_formatter()`,
+    /// which picks `colored_formatter` for an interactive terminal and
+    /// `plain_formatter` otherwise.
+    pub console_formatter: Formatter,
+
+    /// Renderer for the file sink, used when `file_json` is `false`.
+    /// Defaults to `plain_formatter` so rotated log files don't end up full
+    /// of escape codes.
+    pub file_formatter: Formatter,
+
+    /// Rotate the log file once it exceeds this many bytes. `None` (the
+    /// default) disables rotation, matching the previous append-forever
+    /// behavior.
+    pub max_file_bytes: Option<u64>,
+
+    /// How many rotated files to keep alongside the active log file
+    /// (`mining.log.1`, `mining.log.2`, ...). The oldest is deleted once
+    /// this many are already present. Ignored when `max_file_bytes` is
+    /// `None`.
+    pub max_rotated_files: usize,
+
     /// Maximum number of log entries to keep in memory
     pub max_log_entries: usize,
-    
+
     /// Maximum number of hashrate entries to keep in memory
     pub max_hashrate_entries: usize,
-    
-    /// Minimum log level to record
+
+    /// Default minimum log level to record, used for any tag without an
+    /// entry in `tag_levels`. Set together with `tag_levels` via
+    /// `Logger::set_filter`.
     pub level: LevelFilter,
+
+    /// Per-tag level overrides, e.g. `{"opencl": Warn}` to quiet a noisy
+    /// subsystem while leaving everything else at `level`.
+    pub tag_levels: HashMap<String, LevelFilter>,
+
+    /// How many queued records the background writer thread may lag behind
+    /// by before `channel_policy` kicks in.
+    pub channel_bound: usize,
+
+    /// What to do when the writer thread's queue hits `channel_bound`.
+    pub channel_policy: ChannelFullPolicy,
+
+    /// If set, a background thread periodically drains log/hashrate
+    /// entries older than `now - keep_duration`, so a burst of recent
+    /// activity can't evict older-but-still-wanted entries the way the
+    /// count caps (`max_log_entries`, `max_hashrate_entries`) would. Those
+    /// caps stay in effect as a hard ceiling either way.
+    pub keep_duration: Option<chrono::Duration>,
 }
 
 impl Default for LoggerConfig {
@@ -219,19 +341,156 @@ impl Default for LoggerConfig {
             console_output: true,
             file_output: false,
             log_file_path: None,
+            file_json: false,
+            console_formatter: auto_console_formatter(),
+            file_formatter: Formatter::new(plain_formatter),
+            max_file_bytes: None,
+            max_rotated_files: 5,
             max_log_entries: 1000,
             max_hashrate_entries: 1000,
             level: LevelFilter::Info,
+            tag_levels: HashMap::new(),
+            channel_bound: 4096,
+            channel_policy: ChannelFullPolicy::Block,
+            keep_duration: None,
+        }
+    }
+}
+
+impl LoggerConfig {
+    /// Effective minimum level for `tag`: its entry in `tag_levels` if one
+    /// was set via `Logger::set_filter`, else the default `level`.
+    pub fn level_for_tag(&self, tag: &str) -> LevelFilter {
+        self.tag_levels.get(tag).copied().unwrap_or(self.level)
+    }
+}
+
+/// Predicates for [`Logger::query`]; every `Some`/non-default field narrows
+/// the result set further, and all are ANDed together.
+#[derive(Debug, Clone, Default)]
+pub struct RecordFilter {
+    /// Keep records at or above this severity.
+    pub level: Option<LogSeverity>,
+    /// Keep records whose `tag` matches exactly.
+    pub tag: Option<String>,
+    /// Keep records whose `msg` matches this pattern.
+    pub regex: Option<regex::Regex>,
+    /// Drop records older than this timestamp.
+    pub not_before: Option<DateTime<Local>>,
+    /// Cap the number of returned records, newest first. `0` means
+    /// unlimited.
+    pub limit: u32,
+}
+
+/// Shared by `Logger::query` and the live `subscribe` fan-out: does
+/// `record` satisfy every predicate `filter` sets (ANDed together)?
+/// `filter.limit` isn't a per-record predicate, so it's handled separately
+/// by whichever caller collects the matches.
+fn record_matches(record: &LogRecord, filter: &RecordFilter) -> bool {
+    filter.level.map_or(true, |min| record.severity >= min)
+        && filter.tag.as_deref().map_or(true, |tag| record.tag == tag)
+        && filter.regex.as_ref().map_or(true, |re| re.is_match(&record.msg))
+        && filter.not_before.map_or(true, |not_before| record.timestamp >= not_before)
+}
+
+/// A live `subscribe` consumer: a filter (applied to every record before
+/// it's sent) paired with the channel a record is sent down when it
+/// matches.
+struct Subscriber {
+    sender: mpsc::Sender<Arc<LogRecord>>,
+    filter: Option<RecordFilter>,
+}
+
+/// A message handed off to the background writer thread so the producing
+/// thread (often the mining hot path, via `report_hashrate`) never touches
+/// the console, file, or in-memory sinks directly.
+enum LoggerMessage {
+    Log(LogRecord),
+    Hashrate(HashrateEntry),
+    /// Round-trip ack for `Logger::flush`: since the queue is FIFO, by the
+    /// time the writer thread pulls this out, every message sent before it
+    /// has already been processed.
+    Flush(mpsc::Sender<()>),
+    /// Sent by `Logger::drop` once the last strong `Arc` to the `Logger` is
+    /// gone, to wake `run_writer_loop` out of a blocking `recv` so the
+    /// writer thread actually exits instead of lingering forever.
+    Stop,
+}
+
+/// A bounded MPSC queue feeding the writer thread, enforcing
+/// `LoggerConfig::channel_policy` when it's full. `std::sync::mpsc` only
+/// supports blocking on full (`sync_channel`), not drop-oldest, hence the
+/// hand-rolled `Mutex<VecDeque<_>>` + `Condvar` pair instead.
+struct LoggerChannel {
+    queue: Mutex<VecDeque<LoggerMessage>>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    bound: usize,
+    policy: ChannelFullPolicy,
+}
+
+impl LoggerChannel {
+    fn new(bound: usize, policy: ChannelFullPolicy) -> Self {
+        LoggerChannel {
+            queue: Mutex::new(VecDeque::with_capacity(bound.min(1024))),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            bound,
+            policy,
+        }
+    }
+
+    fn send(&self, message: LoggerMessage) {
+        let mut queue = self.queue.lock().unwrap();
+        if self.bound > 0 {
+            match self.policy {
+                ChannelFullPolicy::Block => {
+                    while queue.len() >= self.bound {
+                        queue = self.not_full.wait(queue).unwrap();
+                    }
+                }
+                ChannelFullPolicy::DropOldest => {
+                    if queue.len() >= self.bound {
+                        queue.pop_front();
+                    }
+                }
+            }
+        }
+        queue.push_back(message);
+        self.not_empty.notify_one();
+    }
+
+    fn recv(&self) -> LoggerMessage {
+        let mut queue = self.queue.lock().unwrap();
+        loop {
+            if let Some(message) = queue.pop_front() {
+                self.not_full.notify_one();
+                return message;
+            }
+            queue = self.not_empty.wait(queue).unwrap();
         }
     }
 }
 
+/// Shared stop signal for the cleanup thread: a `Condvar`-backed flag
+/// rather than a plain `AtomicBool` so `Drop` can wake the thread out of
+/// its ~60s sleep immediately instead of waiting for the next tick.
+struct CleanupControl {
+    stop: Mutex<bool>,
+    woken: Condvar,
+}
+
+const CLEANUP_INTERVAL: StdDuration = StdDuration::from_secs(60);
+
 /// The main logger struct
 pub struct Logger {
     logs: RwLock<Vec<LogRecord>>,
     hashrates: RwLock<Vec<HashrateEntry>>,
     config: RwLock<LoggerConfig>,
     file: RwLock<Option<File>>,
+    channel: Arc<LoggerChannel>,
+    cleanup: Arc<CleanupControl>,
+    subscribers: Mutex<Vec<Subscriber>>,
 }
 
 impl Logger {
@@ -252,16 +511,111 @@ impl Logger {
             None
         };
         
+        let channel = Arc::new(LoggerChannel::new(config.channel_bound, config.channel_policy));
+        let cleanup = Arc::new(CleanupControl {
+            stop: Mutex::new(false),
+            woken: Condvar::new(),
+        });
+
         let logger = Arc::new(Self {
             logs: RwLock::new(Vec::with_capacity(config.max_log_entries)),
             hashrates: RwLock::new(Vec::with_capacity(config.max_hashrate_entries)),
             config: RwLock::new(config),
             file: RwLock::new(file),
+            channel: Arc::clone(&channel),
+            cleanup: Arc::clone(&cleanup),
+            subscribers: Mutex::new(Vec::new()),
         });
-        
+
+        // Owns the console/file sinks and the in-memory vectors from here
+        // on; `log`/`report_hashrate` just enqueue and return. Holds only a
+        // `Weak` reference back to the `Logger` (and its own `Arc` to the
+        // channel, which outlives it) so this thread doesn't itself keep
+        // the `Logger` alive forever; `Logger::drop` pushes `Stop` through
+        // the channel to wake it once the last strong `Arc` is gone.
+        let writer_logger = Arc::downgrade(&logger);
+        std::thread::Builder::new()
+            .name("lotus-miner-logger".to_string())
+            .spawn(move || Self::run_writer_loop(writer_logger, channel))?;
+
+        // Periodically reaps entries older than `keep_duration`. Also holds
+        // only a `Weak` reference, for the same reason as the writer thread
+        // above; `Logger::drop` wakes it via `cleanup.woken` to exit once
+        // nothing needs it anymore.
+        let cleaner_logger = Arc::downgrade(&logger);
+        std::thread::Builder::new()
+            .name("lotus-miner-logger-cleanup".to_string())
+            .spawn(move || Self::run_cleanup_loop(cleaner_logger, cleanup))?;
+
         Ok(logger)
     }
-    
+
+    /// Drains `channel` forever, doing all the actual console/file I/O and
+    /// in-memory bookkeeping that `log`/`report_hashrate` used to do on the
+    /// caller's thread. `logger` is a `Weak` reference: if it's already
+    /// gone (every strong `Arc` dropped before this message was pulled),
+    /// the message is simply dropped instead of processed.
+    fn run_writer_loop(logger: Weak<Logger>, channel: Arc<LoggerChannel>) {
+        loop {
+            match channel.recv() {
+                LoggerMessage::Log(record) => {
+                    if let Some(logger) = logger.upgrade() {
+                        logger.write_log_record(record);
+                    }
+                }
+                LoggerMessage::Hashrate(entry) => {
+                    if let Some(logger) = logger.upgrade() {
+                        logger.store_hashrate(entry);
+                    }
+                }
+                LoggerMessage::Flush(ack) => {
+                    let _ = ack.send(());
+                }
+                LoggerMessage::Stop => return,
+            }
+        }
+    }
+
+    /// Sleeps in `CLEANUP_INTERVAL` increments, reaping aged-out log and
+    /// hashrate entries each time it wakes, until `Drop` sets `cleanup.stop`
+    /// and notifies `cleanup.woken` to end the sleep early. `logger` is a
+    /// `Weak` reference, same as `run_writer_loop`'s; if it's already gone
+    /// there's nothing left to reap, so the loop exits instead of looping
+    /// forever on a `Logger` no one holds anymore.
+    fn run_cleanup_loop(logger: Weak<Logger>, cleanup: Arc<CleanupControl>) {
+        loop {
+            let stop = cleanup.stop.lock().unwrap();
+            let (stop, _timed_out) = cleanup.woken.wait_timeout(stop, CLEANUP_INTERVAL).unwrap();
+            let should_stop = *stop;
+            drop(stop);
+            if should_stop {
+                return;
+            }
+            match logger.upgrade() {
+                Some(logger) => logger.reap_aged_entries(),
+                None => return,
+            }
+        }
+    }
+
+    /// Drop log/hashrate entries older than `now - keep_duration`. A no-op
+    /// if `keep_duration` isn't set; the count caps (`max_log_entries`,
+    /// `max_hashrate_entries`) keep applying on every push regardless.
+    fn reap_aged_entries(&self) {
+        let keep_duration = match self.config.read().ok().and_then(|config| config.keep_duration) {
+            Some(duration) => duration,
+            None => return,
+        };
+        let cutoff = Local::now() - keep_duration;
+
+        if let Ok(mut logs) = self.logs.write() {
+            logs.retain(|record| record.timestamp >= cutoff);
+        }
+        if let Ok(mut hashrates) = self.hashrates.write() {
+            hashrates.retain(|entry| entry.timestamp >= cutoff);
+        }
+    }
+
     /// Initialize the logger as the global logger for the log crate
     pub fn init(logger: Arc<Logger>) -> Result<(), LoggerError> {
         let level = {
@@ -277,31 +631,134 @@ impl Logger {
             .map_err(|e| LoggerError::SetLogger(e.to_string()))
     }
     
-    /// Log a message with the given severity
+    /// Queue a message for the writer thread to format, print, and store,
+    /// unless its tag's effective level (`LoggerConfig::level_for_tag`,
+    /// set via `set_filter`) filters it out. Returns immediately: this is
+    /// what keeps logging off the mining hot path (see `report_hashrate`).
     pub fn log(&self, record: impl Into<LogRecord>) {
         let record = record.into();
-        
+        let passes = self
+            .config
+            .read()
+            .map(|config| config.level_for_tag(&record.tag) >= Level::from(record.severity))
+            .unwrap_or(true);
+        if !passes {
+            return;
+        }
+        self.channel.send(LoggerMessage::Log(record));
+    }
+
+    /// Parse a comma-separated directive string such as
+    /// `"info,opencl=warn,share=error"` (an unqualified level sets the
+    /// default; `tag=level` sets a per-tag override) and install it as the
+    /// filter `log`/`LoggerWrapper::enabled` check against. Replaces any
+    /// filter previously set.
+    pub fn set_filter(&self, spec: &str) -> Result<(), LoggerError> {
+        let mut default_level = LevelFilter::Info;
+        let mut tag_levels = HashMap::new();
+
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((tag, level)) => {
+                    let level = level.trim().parse::<LevelFilter>().map_err(|_| {
+                        LoggerError::InvalidDirective(directive.to_string())
+                    })?;
+                    tag_levels.insert(tag.trim().to_string(), level);
+                }
+                None => {
+                    default_level = directive
+                        .parse::<LevelFilter>()
+                        .map_err(|_| LoggerError::InvalidDirective(directive.to_string()))?;
+                }
+            }
+        }
+
+        // The log crate's global max level is a cheap pre-filter the
+        // `log::info!`-style macros check before even calling `enabled`, so
+        // it has to admit the most verbose of the default and all per-tag
+        // overrides or a tag asking for more detail than the default would
+        // get silently dropped upstream of our own filtering.
+        let global_max = tag_levels.values().copied().fold(default_level, LevelFilter::max);
+
+        let mut config = self.config.write().map_err(|e| LoggerError::LockError(e.to_string()))?;
+        config.level = default_level;
+        config.tag_levels = tag_levels;
+        log::set_max_level(global_max);
+        Ok(())
+    }
+
+    /// Block until the writer thread has processed every message queued
+    /// before this call. Used by `LoggerWrapper::flush` and shutdown paths
+    /// that need queued records to have actually reached the file/console
+    /// before they return.
+    pub fn flush(&self) -> Result<(), LoggerError> {
+        let (ack_tx, ack_rx) = mpsc::channel();
+        self.channel.send(LoggerMessage::Flush(ack_tx));
+        ack_rx.recv().map_err(|e| LoggerError::LockError(e.to_string()))
+    }
+
+    /// Format, print, and persist a single record. Only ever called from
+    /// `run_writer_loop`, on the dedicated writer thread.
+    fn write_log_record(&self, record: LogRecord) {
+        let shared = Arc::new(record);
+        self.dispatch_to_subscribers(&shared);
+        // Every other sink below just needs a `&LogRecord`, which `shared`
+        // derefs to; the `Arc` lets `subscribe` consumers share the record
+        // instead of each getting their own clone.
+        let record = shared.as_ref();
+        let mut rotation_error = None;
+
         // Print to console if enabled
         if let Ok(config) = self.config.read() {
             if config.console_output {
-                println!("{}", record);
+                println!("{}", config.console_formatter.format(record));
             }
-            
+
             // Write to file if enabled
             if config.file_output {
                 if let Ok(mut file_guard) = self.file.write() {
                     if let Some(file) = file_guard.as_mut() {
-                        let _ = writeln!(file, "{}", record);
+                        if config.file_json {
+                            if let Ok(line) = serde_json::to_string(&record) {
+                                let _ = writeln!(file, "{}", line);
+                            }
+                        } else {
+                            let _ = writeln!(file, "{}", config.file_formatter.format(record));
+                        }
                         let _ = file.flush();
+
+                        if let Some(max_bytes) = config.max_file_bytes {
+                            let over_limit = file.metadata().map(|meta| meta.len() > max_bytes).unwrap_or(false);
+                            if over_limit {
+                                if let Some(path) = &config.log_file_path {
+                                    match Self::rotate_log_file(path, config.max_rotated_files) {
+                                        Ok(rotated) => *file_guard = Some(rotated),
+                                        Err(err) => {
+                                            rotation_error = Some(format!(
+                                                "Failed to rotate log file {}: {}. Continuing to append to the existing file.",
+                                                path.display(),
+                                                err
+                                            ));
+                                        }
+                                    }
+                                }
+                            }
+                        }
                     }
                 }
             }
         }
-        
+
+        // The file lock above is dropped by now, so this can't deadlock
+        // against the `self.file.write()` it needs.
+        if let Some(err) = rotation_error {
+            self.warn(err, Some("Logger"));
+        }
+
         // Store in memory
         if let Ok(mut logs) = self.logs.write() {
-            logs.push(record);
-            
+            logs.push(record.clone());
+
             // Trim if exceeding max size
             if let Ok(config) = self.config.read() {
                 if logs.len() > config.max_log_entries {
@@ -358,20 +815,88 @@ impl Logger {
             Vec::new()
         }
     }
-    
-    /// Report a hashrate
+
+    /// Filter the in-memory log buffer, newest records first. Lets callers
+    /// (the miner UI, an RPC surface, ...) pull e.g. just the "miner" errors
+    /// from the last minute instead of cloning the whole buffer via
+    /// `get_logs`.
+    pub fn query(&self, filter: &RecordFilter) -> Vec<LogRecord> {
+        let logs = match self.logs.read() {
+            Ok(logs) => logs,
+            Err(_) => return Vec::new(),
+        };
+
+        let mut matched: Vec<LogRecord> = logs
+            .iter()
+            .filter(|r| record_matches(r, filter))
+            .cloned()
+            .collect();
+
+        matched.reverse();
+        if filter.limit > 0 {
+            matched.truncate(filter.limit as usize);
+        }
+        matched
+    }
+
+    /// Subscribe to live log records. Every record accepted by `write_log_record`
+    /// (i.e. already past `config.level_for_tag`) is additionally checked against
+    /// `filter`, if any, and sent down the returned channel. Useful for a
+    /// live-tailing UI pane or a streaming status endpoint that wants just
+    /// `share`/`shutdown` events instead of repeatedly polling `query`.
+    ///
+    /// The channel is unbounded and unpruned on the sender's side beyond
+    /// dropping it once the receiver goes away, so a subscriber that never
+    /// reads will grow memory; callers should drain it regularly or drop it
+    /// when no longer needed.
+    pub fn subscribe(&self, filter: Option<RecordFilter>) -> mpsc::Receiver<Arc<LogRecord>> {
+        let (sender, receiver) = mpsc::channel();
+        if let Ok(mut subscribers) = self.subscribers.lock() {
+            subscribers.push(Subscriber { sender, filter });
+        }
+        receiver
+    }
+
+    /// Fan a freshly written record out to every live subscriber whose filter
+    /// matches, pruning any whose receiver has been dropped. Called from
+    /// `write_log_record`, on the writer thread.
+    fn dispatch_to_subscribers(&self, record: &Arc<LogRecord>) {
+        let mut subscribers = match self.subscribers.lock() {
+            Ok(subscribers) => subscribers,
+            Err(_) => return,
+        };
+
+        subscribers.retain(|subscriber| {
+            let matches = subscriber
+                .filter
+                .as_ref()
+                .map_or(true, |filter| record_matches(record, filter));
+
+            if !matches {
+                return true;
+            }
+
+            subscriber.sender.send(Arc::clone(record)).is_ok()
+        });
+    }
+
+    /// Report a hashrate: logs it (same as any other message, off the hot
+    /// path) and queues the raw entry for the writer thread to store.
     pub fn report_hashrate(&self, hashrate: f64) {
         let entry = HashrateEntry {
             hashrate,
             timestamp: Local::now(),
         };
-        // Log the hashrate with emoji and formatted value
         let formatted = crate::miner::format_hashes_per_sec(hashrate as u64);
         self.info(format!("ðŸ’¯ Hashrate: {}", formatted), Some("General"));
-        // Store in memory
+        self.channel.send(LoggerMessage::Hashrate(entry));
+    }
+
+    /// Store a hashrate entry and trim to `max_hashrate_entries`. Only ever
+    /// called from `run_writer_loop`, on the dedicated writer thread.
+    fn store_hashrate(&self, entry: HashrateEntry) {
         if let Ok(mut hashrates) = self.hashrates.write() {
             hashrates.push(entry);
-            // Trim if exceeding max size
             if let Ok(config) = self.config.read() {
                 if hashrates.len() > config.max_hashrate_entries {
                     let to_keep = config.max_hashrate_entries;
@@ -381,7 +906,7 @@ impl Logger {
             }
         }
     }
-    
+
     /// Get all hashrates
     pub fn hashrates(&self) -> Vec<HashrateEntry> {
         self.hashrates.read().map(|h| h.clone()).unwrap_or_default()
@@ -391,6 +916,16 @@ impl Logger {
     pub fn hashrates_read<'a>(&'a self) -> std::sync::RwLockReadGuard<'a, Vec<HashrateEntry>> {
         self.hashrates.read().unwrap()
     }
+
+    /// Hashrate entries at or after `cutoff` - an exact window, for callers
+    /// (e.g. a "last N minutes" chart) that can't just rely on
+    /// `keep_duration`'s periodic sweep having already trimmed the buffer.
+    pub fn hashrates_since(&self, cutoff: DateTime<Local>) -> Vec<HashrateEntry> {
+        self.hashrates
+            .read()
+            .map(|hashrates| hashrates.iter().filter(|entry| entry.timestamp >= cutoff).cloned().collect())
+            .unwrap_or_default()
+    }
     
     /// Set the log level
     pub fn set_level(&self, level: LevelFilter) -> Result<(), LoggerError> {
@@ -407,6 +942,33 @@ impl Logger {
         Ok(())
     }
     
+    /// Rotate `path`: shift `path.1 -> path.2 -> ... -> path.max_rotated_files`
+    /// (dropping whatever was already at the last slot), move the current
+    /// file to `path.1`, then reopen a fresh file at `path`. Callers must
+    /// hold the `file` write lock for the whole call so no other `log` call
+    /// can write to a half-rotated file.
+    fn rotate_log_file(path: &Path, max_rotated_files: usize) -> io::Result<File> {
+        if max_rotated_files == 0 {
+            std::fs::remove_file(path)?;
+        } else {
+            for i in (1..max_rotated_files).rev() {
+                let from = Self::rotated_path(path, i);
+                if from.exists() {
+                    std::fs::rename(&from, Self::rotated_path(path, i + 1))?;
+                }
+            }
+            std::fs::rename(path, Self::rotated_path(path, 1))?;
+        }
+        OpenOptions::new().write(true).create(true).append(true).open(path)
+    }
+
+    /// Build the rotated sibling path `path.<index>` (e.g. `mining.log.1`).
+    fn rotated_path(path: &Path, index: usize) -> PathBuf {
+        let mut name = path.as_os_str().to_owned();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+
     /// Enable or disable file output and set the file path
     pub fn set_file_output(&self, enabled: bool, path: Option<PathBuf>) -> Result<(), LoggerError> {
         let mut config = self.config.write().map_err(|e| LoggerError::LockError(e.to_string()))?;
@@ -428,21 +990,37 @@ impl Logger {
     }
 }
 
+impl Drop for Logger {
+    /// Both background threads hold only a `Weak` reference to this
+    /// `Logger`, so once the last strong `Arc` is dropped (here), they're
+    /// the only things left needing a nudge to actually exit instead of
+    /// lingering forever. Wakes `run_cleanup_loop` so it exits its sleep
+    /// instead of waiting for up to `CLEANUP_INTERVAL`, and pushes `Stop`
+    /// through the channel to wake `run_writer_loop` out of its blocking
+    /// `recv`.
+    fn drop(&mut self) {
+        if let Ok(mut stop) = self.cleanup.stop.lock() {
+            *stop = true;
+        }
+        self.cleanup.woken.notify_all();
+        self.channel.send(LoggerMessage::Stop);
+    }
+}
+
 /// Implements the backward compatibility with the existing Log struct
 pub struct Log {
     inner: Arc<Logger>,
 }
 
 impl Log {
+    /// A handle to the process-global logger (see [`global_logger`]),
+    /// lazily initializing it with the default configuration on first
+    /// use. Earlier versions constructed a brand new `Logger` - with its
+    /// own writer/cleanup threads - on every call; since this is called
+    /// once per mining round per GPU device during `--genesis` mining,
+    /// that leaked two background threads per call without bound.
     pub fn new() -> Self {
-        // Create a default logger
-        let config = LoggerConfig::default();
-        let logger = Logger::new(config).unwrap();
-        
-        // Try to initialize it as the global logger
-        let _ = Logger::init(Arc::clone(&logger));
-        
-        Self { inner: logger }
+        Self { inner: global_logger() }
     }
     
     pub fn log(&self, entry: impl Into<LogEntry>) {
@@ -474,7 +1052,21 @@ impl Log {
     pub fn get_logs_and_clear(&self) -> Vec<LogEntry> {
         self.inner.get_logs_and_clear()
     }
-    
+
+    pub fn query(&self, filter: &RecordFilter) -> Vec<LogRecord> {
+        self.inner.query(filter)
+    }
+
+    pub fn subscribe(&self, filter: Option<RecordFilter>) -> mpsc::Receiver<Arc<LogRecord>> {
+        self.inner.subscribe(filter)
+    }
+
+    /// Block until every message queued before this call has reached the
+    /// console/file/in-memory sinks. Useful right before the process exits.
+    pub fn flush(&self) -> Result<(), LoggerError> {
+        self.inner.flush()
+    }
+
     pub fn report_hashrate(&self, hashrate: f64) {
         self.inner.report_hashrate(hashrate);
     }
@@ -482,6 +1074,10 @@ impl Log {
     pub fn hashrates<'a>(&'a self) -> std::sync::RwLockReadGuard<'a, Vec<HashrateEntry>> {
         self.inner.hashrates_read()
     }
+
+    pub fn hashrates_since(&self, cutoff: DateTime<Local>) -> Vec<HashrateEntry> {
+        self.inner.hashrates_since(cutoff)
+    }
 }
 
 /// Wrapper for the Logger to implement the log::Log trait
@@ -490,7 +1086,10 @@ struct LoggerWrapper(Arc<Logger>);
 impl LogTrait for LoggerWrapper {
     fn enabled(&self, metadata: &Metadata) -> bool {
         if let Ok(config) = self.0.config.read() {
-            metadata.level() <= config.level
+            // `target()` is the closest thing `Metadata` has to our `tag`
+            // concept (and matches the `target=level` directive syntax
+            // `set_filter` accepts, mirroring env_logger's `RUST_LOG`).
+            metadata.level() <= config.level_for_tag(metadata.target())
         } else {
             // If we can't read the config, assume enabled
             true
@@ -511,6 +1110,7 @@ impl LogTrait for LoggerWrapper {
     }
     
     fn flush(&self) {
+        let _ = self.0.flush();
         if let Ok(file_guard) = self.0.file.write() {
             if let Some(file) = file_guard.as_ref() {
                 let _ = file.sync_all();
@@ -522,26 +1122,25 @@ impl LogTrait for LoggerWrapper {
 // Initialize a global static logger that can be accessed from anywhere
 static LOGGER_INITIALIZED: AtomicBool = AtomicBool::new(false);
 
+/// The actual process-global `Logger`, set exactly once by whichever of
+/// `init_global_logger`/`init_default_logger`/`Log::new` runs first.
+/// Sharing this one `Arc` (rather than each caller constructing its own
+/// `Logger::new`) is what keeps a `Logger`'s background threads and
+/// channel from being spun up over and over.
+static GLOBAL_LOGGER: OnceLock<Arc<Logger>> = OnceLock::new();
+
 /// Get the global logger instance
 pub fn get_global_logger() -> Result<Arc<Logger>, LoggerError> {
-    if !LOGGER_INITIALIZED.load(Ordering::SeqCst) {
-        return Err(LoggerError::NotInitialized);
-    }
-    
-    // Since we can't directly access the logger from the log crate,
-    // we'll create a new one with the current settings
-    let config = LoggerConfig {
-        level: log::max_level(),
-        ..Default::default()
-    };
-    
-    Logger::new(config)
+    GLOBAL_LOGGER.get().cloned().ok_or(LoggerError::NotInitialized)
 }
 
 /// Initialize the global logger with custom configuration
 pub fn init_global_logger(config: LoggerConfig) -> Result<Arc<Logger>, LoggerError> {
     let logger = Logger::new(config)?;
     Logger::init(Arc::clone(&logger))?;
+    GLOBAL_LOGGER
+        .set(Arc::clone(&logger))
+        .map_err(|_| LoggerError::SetLogger("global logger already initialized".to_string()))?;
     LOGGER_INITIALIZED.store(true, Ordering::SeqCst);
     Ok(logger)
 }
@@ -550,3 +1149,19 @@ pub fn init_global_logger(config: LoggerConfig) -> Result<Arc<Logger>, LoggerErr
 pub fn init_default_logger() -> Result<Arc<Logger>, LoggerError> {
     init_global_logger(LoggerConfig::default())
 }
+
+/// The `Arc<Logger>` behind every `Log::new()`: the already-initialized
+/// global logger if one exists, otherwise one lazily initialized here with
+/// the default configuration. `OnceLock::get_or_init` makes concurrent
+/// first calls (e.g. one per GPU device in the `--genesis` mining loop)
+/// race-safe - only one of them actually constructs a `Logger`.
+fn global_logger() -> Arc<Logger> {
+    GLOBAL_LOGGER
+        .get_or_init(|| {
+            let logger = Logger::new(LoggerConfig::default()).expect("failed to construct default logger");
+            let _ = Logger::init(Arc::clone(&logger));
+            LOGGER_INITIALIZED.store(true, Ordering::SeqCst);
+            logger
+        })
+        .clone()
+}
@@ -1,5 +1,6 @@
 use ocl::{
     builders::{DeviceSpecifier, ProgramBuilder},
+    enums::{KernelWorkGroupInfo, KernelWorkGroupInfoResult},
     Buffer, Context, Device, Kernel, Platform, Queue,
 };
 use sha2::Digest;
@@ -77,18 +78,147 @@ impl Default for KernelType {
     }
 }
 
+/// Which OpenCL device(s) a miner instance should bind to, as configured via
+/// `gpu_index` — either one or more explicit device indices, or `All` to
+/// enumerate every device [`Miner::list_device_names`] would report.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GpuSelection {
+    Indices(Vec<u32>),
+    All,
+}
+
+impl GpuSelection {
+    /// Resolve this selection to concrete device indices, enumerating every
+    /// available OpenCL device when `self` is `All`.
+    pub fn resolve(&self) -> Vec<usize> {
+        match self {
+            GpuSelection::Indices(indices) => indices.iter().map(|&i| i as usize).collect(),
+            GpuSelection::All => (0..Miner::device_count()).collect(),
+        }
+    }
+}
+
+impl Default for GpuSelection {
+    fn default() -> Self {
+        GpuSelection::Indices(vec![0])
+    }
+}
+
+impl std::fmt::Display for GpuSelection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GpuSelection::Indices(indices) => {
+                let rendered: Vec<String> = indices.iter().map(|i| i.to_string()).collect();
+                write!(f, "{}", rendered.join(", "))
+            }
+            GpuSelection::All => write!(f, "all"),
+        }
+    }
+}
+
+/// Coarse GPU vendor classification, detected from a device's vendor string
+/// and parent platform name (mirroring ethminer's `OPENCL_PLATFORM_NVIDIA`/
+/// `AMD` detection), so `Miner::setup` can pick vendor-tuned kernel compiler
+/// defines and a default local work size instead of one-size-fits-all
+/// constants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuVendor {
+    Nvidia,
+    Amd,
+    Other,
+}
+
+impl GpuVendor {
+    fn detect(device_vendor: &str, platform_name: &str) -> Self {
+        let haystack = format!("{} {}", device_vendor, platform_name).to_lowercase();
+        if haystack.contains("nvidia") {
+            GpuVendor::Nvidia
+        } else if haystack.contains("amd") || haystack.contains("advanced micro devices") {
+            GpuVendor::Amd
+        } else {
+            GpuVendor::Other
+        }
+    }
+
+    /// The compiler macro fed into `ProgramBuilder::cmplr_def`, letting the
+    /// embedded kernel source branch on `#ifdef PLATFORM_AMD` for
+    /// `cl_amd_media_ops` bitalign paths or `#ifdef PLATFORM_NVIDIA` for
+    /// NVIDIA-specific intrinsics.
+    fn platform_define(self) -> &'static str {
+        match self {
+            GpuVendor::Nvidia => "PLATFORM_NVIDIA",
+            GpuVendor::Amd => "PLATFORM_AMD",
+            GpuVendor::Other => "PLATFORM_GENERIC",
+        }
+    }
+
+    /// A starting point for local work size before clamping to the device
+    /// and kernel's actual limits: AMD GPUs are organized into wavefronts of
+    /// 64, NVIDIA into warps of 32.
+    fn default_local_work_size(self) -> u32 {
+        match self {
+            GpuVendor::Nvidia => 32,
+            GpuVendor::Amd => 64,
+            GpuVendor::Other => 64,
+        }
+    }
+}
+
+/// Clamp `vendor`'s default local work size to what `device` and
+/// `kernel` actually support, querying `CL_DEVICE_MAX_WORK_GROUP_SIZE` and
+/// the kernel's own `CL_KERNEL_WORK_GROUP_SIZE` so a wavefront/warp-sized
+/// default never exceeds a smaller or more restrictive device/kernel combo.
+fn resolve_local_work_size(vendor: GpuVendor, device: Device, kernel: &Kernel) -> u32 {
+    let mut local_work_size = vendor.default_local_work_size();
+
+    if let Ok(max_wg_size) = device.max_wg_size() {
+        local_work_size = local_work_size.min(max_wg_size as u32);
+    }
+
+    if let Ok(KernelWorkGroupInfoResult::WorkGroupSize(max_kernel_wg_size)) =
+        kernel.wg_info(device, KernelWorkGroupInfo::WorkGroupSize)
+    {
+        local_work_size = local_work_size.min(max_kernel_wg_size as u32);
+    }
+
+    local_work_size.max(1)
+}
+
 pub struct Miner {
     search_kernel: Kernel,
     header_buffer: Buffer<u32>,
-    buffer: Buffer<u32>,
+    /// Ping-pong pair of output buffers; `find_nonce` alternates which one
+    /// the kernel writes into each dispatch so it can read back the other
+    /// one (from the previous dispatch) while the device is still busy with
+    /// the current one, instead of idling on every batch's readback.
+    buffers: [Buffer<u32>; 2],
+    /// One command queue per ping-pong slot; see `buffers`.
+    queues: [Queue; 2],
     settings: MiningSettings,
     kernel_type: KernelType,
+    /// The selected device's detected vendor, used to pick the compiler
+    /// define passed to `ProgramBuilder` and `local_work_size`'s default.
+    vendor: GpuVendor,
+    /// The POCLBM dispatch's local work size, resolved once in `setup` from
+    /// `vendor`'s default and clamped to this device/kernel's actual limits;
+    /// see `resolve_local_work_size`.
+    local_work_size: u32,
 }
 
 #[derive(Debug, Clone, Copy)]
 pub struct Work {
     header: [u8; 160],
-    target: [u8; 32],
+    /// Target a result must beat to count as a submittable share, via the
+    /// existing `Statistics`/pool-submission counters. In solo/RPC-pool
+    /// mode this is simply the block target (every share there IS the
+    /// block); in Stratum mode it's the pool's `mining.set_difficulty`
+    /// target, which is normally much easier than `block_target`.
+    share_target: [u8; 32],
+    /// The network's actual block-solution target, used only to flag a
+    /// found nonce as a full block (see `find_nonce`'s return value) on top
+    /// of whatever `share_target` already reports. Equal to `share_target`
+    /// unless overridden via `with_block_target`.
+    block_target: [u8; 32],
     pub nonce_idx: u32,
 }
 
@@ -104,11 +234,21 @@ impl Work {
     pub fn from_header(header: [u8; 160], target: [u8; 32]) -> Work {
         Work {
             header,
-            target,
+            share_target: target,
+            block_target: target,
             nonce_idx: 0,
         }
     }
 
+    /// Override the block-solution target when it differs from the share
+    /// target passed to `from_header` — e.g. a Stratum pool's per-share
+    /// target decoded via `pow::difficulty_to_target`, paired with the
+    /// job's own `n_bits` decoded via `pow::nbits_to_target`.
+    pub fn with_block_target(mut self, block_target: [u8; 32]) -> Work {
+        self.block_target = block_target;
+        self
+    }
+
     pub fn set_big_nonce(&mut self, big_nonce: u64) {
         self.header[44..52].copy_from_slice(&big_nonce.to_le_bytes());
     }
@@ -116,13 +256,22 @@ impl Work {
     pub fn header(&self) -> &[u8; 160] {
         &self.header
     }
+
+    pub(crate) fn share_target(&self) -> &[u8; 32] {
+        &self.share_target
+    }
+
+    pub(crate) fn block_target(&self) -> &[u8; 32] {
+        &self.block_target
+    }
 }
 
 impl Default for Work {
     fn default() -> Self {
         Work {
             header: [0; 160],
-            target: [0; 32],
+            share_target: [0; 32],
+            block_target: [0; 32],
             nonce_idx: 0,
         }
     }
@@ -152,6 +301,22 @@ pub fn format_number(value: u64) -> String {
     chars.into_iter().rev().collect()
 }
 
+/// Unsigned comparison of `hash` against `target`, both read as
+/// little-endian 256-bit numbers (byte 31 is the most significant and is
+/// compared first), matching the byte ordering `scan_buffer`'s share check
+/// already assumes.
+pub(crate) fn hash_meets_target(hash: &[u8; 32], target: &[u8; 32]) -> bool {
+    for (&h, &t) in hash.iter().zip(target.iter()).rev() {
+        if h > t {
+            return false;
+        }
+        if t > h {
+            return true;
+        }
+    }
+    true
+}
+
 // Format bytes as B, kB, MB, GB, etc.
 pub fn format_bytes(value: u64) -> String {
     let units = ["B", "kB", "MB", "GB", "TB", "PB", "EB", "ZB", "YB"];
@@ -184,7 +349,20 @@ pub fn format_hashes_per_sec(value: u64) -> String {
     }
 }
 
-fn mining_runtime_stats() -> String {
+/// Increment the shared share counter, returning the new total. Used by
+/// `crate::cpu_miner::CpuMiner::find_nonce`'s share-found path; `scan_buffer`
+/// increments `SHARES_FOUND` directly, so both backends count into the same
+/// total no matter which device found it.
+pub(crate) fn record_share() -> u64 {
+    SHARES_FOUND.fetch_add(1, Ordering::Relaxed) + 1
+}
+
+/// Add `count` to the shared total-hashes-processed counter.
+pub(crate) fn record_hashes(count: u64) {
+    HASHES_PROCESSED.fetch_add(count, Ordering::Relaxed);
+}
+
+pub(crate) fn mining_runtime_stats() -> String {
     let runtime = MINING_START_TIME.elapsed();
     let hours = runtime.as_secs() / 3600;
     let minutes = (runtime.as_secs() % 3600) / 60;
@@ -257,7 +435,11 @@ impl Miner {
         let platform_name = platform.name().unwrap_or_else(|_| String::from("Unknown platform"));
         let device_name = device.name().unwrap_or_else(|_| String::from("Unknown device"));
         info!("‚úÖ Selected GPU: {} from platform: {}", device_name, platform_name);
-        
+
+        let device_vendor = device.vendor().unwrap_or_else(|_| String::from("Unknown"));
+        let vendor = GpuVendor::detect(&device_vendor, &platform_name);
+        info!("Detected GPU vendor: {:?} (device vendor string: {})", vendor, device_vendor);
+
         // Create context with the selected device
         let context = Context::builder()
             .platform(platform)
@@ -266,9 +448,14 @@ impl Miner {
             
         debug!("üîß OpenCL context created successfully");
         
-        // Create command queue
-        let queue = Queue::new(&context, device, None)?;
-        debug!("üîÑ Command queue created successfully");
+        // Create one command queue per ping-pong slot: each slot's buffer
+        // I/O and kernel dispatch runs on its own queue, so slot B's kernel
+        // can be running on the device while slot A's result buffer is
+        // still being read back on queue A, instead of a single in-order
+        // queue serializing every write/compute/read in lockstep.
+        let queue_a = Queue::new(&context, device, None)?;
+        let queue_b = Queue::new(&context, device, None)?;
+        debug!("Command queues created successfully");
         
         // Setup and build the kernels using embedded kernel code
         let mut prog_builder = ProgramBuilder::new();
@@ -290,7 +477,7 @@ impl Miner {
             KernelType::LotusOG => (settings.local_work_size, settings.inner_iter_size),
             KernelType::POCLBM => {
                 // POCLBM kernel often works better with these values
-                let poclbm_local_size = 64; // Common value for POCLBM kernel
+                let poclbm_local_size = vendor.default_local_work_size() as i32; // Wavefront/warp-sized default, refined below
                 let poclbm_inner_size = 8;  // Reduced value to avoid work group size issues
                 
                 info!("üîß Adjusting POCLBM kernel parameters: local_work_size={}, inner_iter_size={}",
@@ -303,7 +490,8 @@ impl Miner {
         prog_builder
             .src(kernel_code)
             .cmplr_def("WORKSIZE", local_work_size)
-            .cmplr_def("ITERATIONS", inner_iter_size);
+            .cmplr_def("ITERATIONS", inner_iter_size)
+            .cmplr_def(vendor.platform_define(), 1);
         
         // Add device to program
         prog_builder.devices(DeviceSpecifier::Single(device));
@@ -317,10 +505,13 @@ impl Miner {
         kernel_builder
             .program(&program)
             .name("search")
-            .queue(queue.clone());
+            .queue(queue_a.clone());
             
-        let buffer = Buffer::builder().len(0xff).queue(queue.clone()).build()?;
-        let header_buffer = Buffer::builder().len(0xff).queue(queue).build()?;
+        let buffers = [
+            Buffer::builder().len(0xff).queue(queue_a.clone()).build()?,
+            Buffer::builder().len(0xff).queue(queue_b.clone()).build()?,
+        ];
+        let header_buffer = Buffer::builder().len(0xff).queue(queue_a.clone()).build()?;
         
         debug!("üß† OpenCL buffers allocated successfully");
         
@@ -353,13 +544,19 @@ impl Miner {
         
         // Create the miner with the kernel type
         let kernel_type = settings.kernel_type;
-        
+
+        let resolved_local_work_size = resolve_local_work_size(vendor, device, &search_kernel);
+        info!("Resolved POCLBM local_work_size: {}", resolved_local_work_size);
+
         Ok(Miner {
             search_kernel,
-            buffer,
+            buffers,
+            queues: [queue_a, queue_b],
             header_buffer,
             settings,
             kernel_type,
+            vendor,
+            local_work_size: resolved_local_work_size,
         })
     }
 
@@ -403,6 +600,35 @@ impl Miner {
         device_names
     }
 
+    /// Total number of OpenCL devices across all platforms, used to expand
+    /// the `"all"` [`GpuSelection`] sentinel into concrete device indices.
+    pub fn device_count() -> usize {
+        Platform::list()
+            .iter()
+            .map(|platform| Device::list_all(platform).map(|d| d.len()).unwrap_or(0))
+            .sum()
+    }
+
+    /// OpenCL device indices this miner instance was configured with.
+    pub fn gpu_indices(&self) -> &[usize] {
+        &self.settings.gpu_indices
+    }
+
+    pub fn kernel_size(&self) -> u32 {
+        self.settings.kernel_size
+    }
+
+    pub fn local_work_size(&self) -> u32 {
+        self.local_work_size
+    }
+
+    /// Overwrite this miner's `kernel_size` and POCLBM `local_work_size` with
+    /// a previously cached `autotune` result, skipping the sweep entirely.
+    pub fn apply_tuning(&mut self, kernel_size: u32, local_work_size: u32) {
+        self.settings.kernel_size = kernel_size;
+        self.local_work_size = local_work_size;
+    }
+
     pub fn has_nonces_left(&self, work: &Work) -> bool {
         work.nonce_idx
             .checked_mul(self.settings.kernel_size)
@@ -413,7 +639,11 @@ impl Miner {
         self.settings.kernel_size as u64 * self.settings.inner_iter_size as u64
     }
 
-    pub fn find_nonce(&mut self, work: &Work, log: &Log) -> Result<Option<u64>> {
+    /// Search this batch for a nonce that meets `work.share_target`. Returns
+    /// `Some((nonce, is_block))` where `is_block` additionally flags whether
+    /// the hash also meets the stricter `work.block_target` — e.g. a Stratum
+    /// share that happens to also solve the actual block.
+    pub fn find_nonce(&mut self, work: &Work, log: &Log) -> Result<Option<(u64, bool)>> {
         let base = match work
             .nonce_idx
             .checked_mul(self.num_nonces_per_search().try_into().unwrap())
@@ -421,16 +651,16 @@ impl Miner {
             Some(base) => base,
             None => {
                 log.error(
-                    "üö® Error: Nonce base overflow, skipping. This could be fixed by lowering rpc_poll_interval.",
+                    "Error: Nonce base overflow, skipping. This could be fixed by lowering rpc_poll_interval.",
                     Some("Miner")
                 );
                 return Ok(None);
             }
         };
-        
+
         // Track the time it takes to process this batch
         let batch_start = Instant::now();
-        
+
         let mut partial_header = [0u8; 84];
         partial_header[..52].copy_from_slice(&work.header[..52]);
         partial_header[52..].copy_from_slice(&sha2::Sha256::digest(&work.header[52..]));
@@ -438,68 +668,34 @@ impl Miner {
         for (chunk, int) in partial_header.chunks(4).zip(partial_header_ints.iter_mut()) {
             *int = u32::from_be_bytes(chunk.try_into().unwrap());
         }
-        
-        debug!("üßÆ Processing nonce batch starting at base: {}", base);
-        
-        // Write header data to buffer
+
+        debug!("Processing nonce batch starting at base: {}", base);
+
+        // The header is identical for both halves of this batch (only the
+        // "offset" argument advances between them), so it only needs writing
+        // once.
         self.header_buffer.write(&partial_header_ints[..]).enq().map_err(Ocl)?;
-        
-        // Use the mine method to set the kernel arguments based on kernel type
-        match self.kernel_type {
-            KernelType::LotusOG => {
-                // Set the arguments for Lotus OG kernel
-                self.search_kernel
-                    .set_arg("offset", base).map_err(Ocl)?;
-                self.search_kernel
-                    .set_arg("partial_header", &self.header_buffer).map_err(Ocl)?;
-                self.search_kernel
-                    .set_arg("output", &self.buffer).map_err(Ocl)?;
-            },
-            KernelType::POCLBM => {
-                // Use the same simple argument setting for POCLBM
-                self.search_kernel
-                    .set_arg("offset", base).map_err(Ocl)?;
-                self.search_kernel
-                    .set_arg("partial_header", &self.header_buffer).map_err(Ocl)?;
-                self.search_kernel
-                    .set_arg("output", &self.buffer).map_err(Ocl)?;
-            }
-        }
-        
-        let mut vec = vec![0; self.buffer.len()];
-        self.buffer.write(&vec).enq().map_err(Ocl)?;
-        
-        // Setup kernel execution with appropriate work group size based on kernel type
-        let cmd = match self.kernel_type {
-            KernelType::LotusOG => {
-                // For Lotus OG kernel, we can use the original settings
-                self.search_kernel
-                    .cmd()
-                    .global_work_size(self.settings.kernel_size)
-            },
-            KernelType::POCLBM => {
-                // For POCLBM kernel, we need to set both global and local work sizes
-                // The POCLBM kernel typically requires a local work size that is a power of 2
-                // and meets alignment requirements
-                let local_work_size = 64; // Common value that works on most GPUs
-                debug!("üîß Using local_work_size={} for POCLBM kernel", local_work_size);
-                
-                self.search_kernel
-                    .cmd()
-                    .global_work_size(self.settings.kernel_size)
-                    .local_work_size(local_work_size)
-            }
-        };
-        
-        unsafe {
-            cmd.enq().map_err(Ocl)?;
+
+        // Split this call's batch into a ping-ponged pair of dispatches:
+        // launch slot 1's kernel (a non-blocking `unsafe enq()`, on its own
+        // queue) right after slot 0's, then scan slot 0's readback while
+        // slot 1 is still computing, instead of serializing
+        // write/compute/read once per batch on a single queue.
+        let half = (self.settings.kernel_size / 2).max(1);
+        let second_half = self.settings.kernel_size.saturating_sub(half).max(1);
+
+        self.dispatch_chunk(0, base, half)?;
+        self.dispatch_chunk(1, base.saturating_add(half as u64), second_half)?;
+
+        let mut winner = self.scan_buffer(0, work, log)?;
+        if winner.is_none() {
+            winner = self.scan_buffer(1, work, log)?;
         }
-        self.buffer.read(&mut vec).enq().map_err(Ocl)?;
-        
+
         // Update total hashes processed
         let hashes_in_batch = self.num_nonces_per_search();
-        let _current_total = HASHES_PROCESSED.fetch_add(hashes_in_batch, Ordering::Relaxed);
-        
+        HASHES_PROCESSED.fetch_add(hashes_in_batch, Ordering::Relaxed);
+
         // Calculate batch speed
         let batch_time = batch_start.elapsed();
         let speed = if batch_time.as_secs_f64() > 0.0 {
@@ -507,63 +703,120 @@ impl Miner {
         } else {
             0.0
         };
-        
+
         if work.nonce_idx % 100 == 0 {
-            debug!("‚ö° Batch speed: {:.2} MH/s | {}", speed, mining_runtime_stats());
+            debug!("Batch speed: {:.2} MH/s | {}", speed, mining_runtime_stats());
         }
-        
-        if vec[0x80] != 0 {
-            let mut header = work.header;
-            'nonce: for &nonce in &vec[..0x7f] {
-                let nonce = nonce.swap_bytes();
-                if nonce != 0 {
-                    header[44..48].copy_from_slice(&nonce.to_le_bytes());
-                    let result_nonce = u64::from_le_bytes(header[44..52].try_into().unwrap());
-                    let hash = lotus_hash(&header);
-                    let mut candidate_hash = hash;
-                    candidate_hash.reverse();
-                    
-                    log.info(
-                        format!(
-                            "üîç Candidate: nonce={}, hash={}",
-                            result_nonce,
-                            hex::encode(&candidate_hash)
-                        ),
+
+        Ok(winner)
+    }
+
+    /// Zero ping-pong slot `slot`'s output buffer and enqueue the search
+    /// kernel against it for `global_size` work-items starting at `offset`,
+    /// on that slot's own queue. The `unsafe enq()` only submits the
+    /// command; it doesn't block on completion, which is what lets the
+    /// caller launch the other slot's dispatch (or scan a previous one)
+    /// while this one is still running on the device.
+    fn dispatch_chunk(&mut self, slot: usize, offset: u64, global_size: u32) -> Result<()> {
+        let zeros = vec![0u32; self.buffers[slot].len()];
+        self.buffers[slot].write(&zeros).enq().map_err(Ocl)?;
+
+        self.search_kernel.set_arg("offset", offset).map_err(Ocl)?;
+        self.search_kernel
+            .set_arg("partial_header", &self.header_buffer).map_err(Ocl)?;
+        self.search_kernel
+            .set_arg("output", &self.buffers[slot]).map_err(Ocl)?;
+
+        let cmd = match self.kernel_type {
+            KernelType::LotusOG => self.search_kernel
+                .cmd()
+                .queue(&self.queues[slot])
+                .global_work_size(global_size),
+            KernelType::POCLBM => {
+                // Vendor-tuned default, clamped to this device/kernel's actual
+                // limits in `resolve_local_work_size` during `setup`.
+                self.search_kernel
+                    .cmd()
+                    .queue(&self.queues[slot])
+                    .global_work_size(global_size)
+                    .local_work_size(self.local_work_size)
+            }
+        };
+
+        unsafe {
+            cmd.enq().map_err(Ocl)?;
+        }
+        Ok(())
+    }
+
+    /// Block until slot `slot`'s dispatch has finished (the `read()` enqueue
+    /// on that slot's queue waits for the kernel that wrote it), then scan
+    /// its output for a nonce whose hash clears `work.share_target`.
+    fn scan_buffer(&self, slot: usize, work: &Work, log: &Log) -> Result<Option<(u64, bool)>> {
+        let mut vec = vec![0u32; self.buffers[slot].len()];
+        self.buffers[slot].read(&mut vec).enq().map_err(Ocl)?;
+
+        if vec[0x80] == 0 {
+            return Ok(None);
+        }
+
+        let mut header = work.header;
+        'nonce: for &nonce in &vec[..0x7f] {
+            let nonce = nonce.swap_bytes();
+            if nonce != 0 {
+                header[44..48].copy_from_slice(&nonce.to_le_bytes());
+                let result_nonce = u64::from_le_bytes(header[44..52].try_into().unwrap());
+                let hash = lotus_hash(&header);
+                let mut candidate_hash = hash;
+                candidate_hash.reverse();
+
+                log.info(
+                    format!(
+                        "Candidate: nonce={}, hash={}",
+                        result_nonce,
+                        hex::encode(&candidate_hash)
+                    ),
+                    Some("Share")
+                );
+
+                if hash.last() != Some(&0) {
+                    log.bug(
+                        "Bug: found nonce's hash has no leading zero byte. Contact the developers.",
                         Some("Share")
                     );
-                    
-                    if hash.last() != Some(&0) {
-                        log.bug(
-                            "üêû Bug: found nonce's hash has no leading zero byte. Contact the developers.",
+                }
+
+                for (&h, &t) in hash.iter().zip(work.share_target.iter()).rev() {
+                    if h > t {
+                        continue 'nonce;
+                    }
+                    if t > h {
+                        // Increment share counter
+                        let shares = SHARES_FOUND.fetch_add(1, Ordering::Relaxed) + 1;
+                        let _timestamp = Local::now().format("%Y-%m-%d %H:%M:%S");
+                        let is_block = hash_meets_target(&hash, &work.block_target);
+
+                        // Celebratory log message with stats
+                        log.info(
+                            format!("Found valid share #{} at nonce {}", shares, result_nonce),
                             Some("Share")
                         );
-                    }
-                    
-                    for (&h, &t) in hash.iter().zip(work.target.iter()).rev() {
-                        if h > t {
-                            continue 'nonce;
-                        }
-                        if t > h {
-                            // Increment share counter
-                            let shares = SHARES_FOUND.fetch_add(1, Ordering::Relaxed) + 1;
-                            let _timestamp = Local::now().format("%Y-%m-%d %H:%M:%S");
-                            
-                            // Celebratory log message with stats
-                            log.info(
-                                format!("üí∞ Found valid share #{} at nonce {} üí∞", shares, result_nonce),
-                                Some("Share")
-                            );
-                            log.info(
-                                format!("üéä Hash: {} üéä", hex::encode(&candidate_hash)),
-                                Some("Share")
-                            );
+                        log.info(
+                            format!("Hash: {}", hex::encode(&candidate_hash)),
+                            Some("Share")
+                        );
+                        log.info(
+                            format!("Stats: {}", mining_runtime_stats()),
+                            Some("Share")
+                        );
+                        if is_block {
                             log.info(
-                                format!("üìä Stats: {}", mining_runtime_stats()),
+                                "This share also clears the full block target!",
                                 Some("Share")
                             );
-                            
-                            return Ok(Some(result_nonce));
                         }
+
+                        return Ok(Some((result_nonce, is_block)));
                     }
                 }
             }
@@ -576,6 +829,100 @@ impl Miner {
         info!("üî• Mining intensity set to {} (kernel size: {})", intensity, self.settings.kernel_size);
     }
 
+    /// Sweep a small range of `kernel_size` (and, for POCLBM, `local_work_size`)
+    /// combinations against a dummy zero-target header, timing a few
+    /// `find_nonce`-style batches at each, and keep whichever combination
+    /// reaches the highest hashes/sec. A combination that errors out (e.g. an
+    /// oversized local work size triggering `CL_INVALID_WORK_GROUP_SIZE` or an
+    /// out-of-resources error) is rejected rather than aborting the sweep.
+    /// Leaves `self.settings.kernel_size`/`self.local_work_size` set to the
+    /// winning combination (or the original one, if every combination
+    /// errored); `Server::from_config` reads them back afterwards to persist
+    /// via `settings::save_tuned_device`.
+    pub fn autotune(&mut self, log: &Log) -> Result<()> {
+        const INTENSITY_RANGE: std::ops::RangeInclusive<u32> = 18..=24;
+        const TRIAL_BATCHES: u32 = 3;
+
+        let original_settings = self.settings.clone();
+        let original_local_work_size = self.local_work_size;
+
+        let local_work_size_candidates: Vec<u32> = match self.kernel_type {
+            KernelType::LotusOG => vec![original_local_work_size],
+            KernelType::POCLBM => [32, 64, 128, 256, 512, 1024]
+                .into_iter()
+                .filter(|&size| size <= original_local_work_size.max(32))
+                .collect(),
+        };
+
+        info!(
+            "Auto-tuning kernel_size/local_work_size for GPU {:?}...",
+            self.settings.gpu_indices
+        );
+
+        let dummy_work = Work::default();
+        let mut best: Option<(u32, u32, f64)> = None;
+
+        for intensity in INTENSITY_RANGE {
+            let candidate_kernel_size = 1u32 << intensity;
+            for &candidate_local_work_size in &local_work_size_candidates {
+                self.settings.kernel_size = candidate_kernel_size;
+                self.local_work_size = candidate_local_work_size;
+
+                let trial_start = Instant::now();
+                let mut trial_work = dummy_work;
+                let mut failed = false;
+                for batch in 0..TRIAL_BATCHES {
+                    trial_work.nonce_idx = batch;
+                    if let Err(err) = self.find_nonce(&trial_work, log) {
+                        debug!(
+                            "Auto-tune: rejecting kernel_size={}, local_work_size={}: {:?}",
+                            candidate_kernel_size, candidate_local_work_size, err
+                        );
+                        failed = true;
+                        break;
+                    }
+                }
+                if failed {
+                    continue;
+                }
+
+                let elapsed = trial_start.elapsed().as_secs_f64().max(0.000_001);
+                let hashes = self.num_nonces_per_search() as f64 * TRIAL_BATCHES as f64;
+                let hashes_per_sec = hashes / elapsed;
+
+                debug!(
+                    "Auto-tune: kernel_size={}, local_work_size={} -> {:.2} MH/s",
+                    candidate_kernel_size,
+                    candidate_local_work_size,
+                    hashes_per_sec / 1_000_000.0
+                );
+
+                if best.as_ref().map_or(true, |&(_, _, best_rate)| hashes_per_sec > best_rate) {
+                    best = Some((candidate_kernel_size, candidate_local_work_size, hashes_per_sec));
+                }
+            }
+        }
+
+        self.settings = original_settings;
+        match best {
+            Some((kernel_size, local_work_size, hashes_per_sec)) => {
+                self.settings.kernel_size = kernel_size;
+                self.local_work_size = local_work_size;
+                info!(
+                    "Auto-tune complete: kernel_size={}, local_work_size={} ({:.2} MH/s)",
+                    kernel_size,
+                    local_work_size,
+                    hashes_per_sec / 1_000_000.0
+                );
+            }
+            None => {
+                self.local_work_size = original_local_work_size;
+                error!("Auto-tune failed: every kernel_size/local_work_size combination errored, keeping existing settings");
+            }
+        }
+        Ok(())
+    }
+
     pub fn update_gpu_index(&mut self, gpu_index: i64) -> Result<()> {
         if self.settings.gpu_indices[0] == gpu_index as usize {
             info!("‚ÑπÔ∏è GPU index {} is already selected, no change needed", gpu_index);
@@ -610,8 +957,8 @@ impl Miner {
         
         // Proceed with standard mining operation
         match self.find_nonce(work, log) {
-            Ok(Some(nonce)) => {
-                log.info(format!("üíé Found potential solution with nonce: {}", nonce), Some("Share"));
+            Ok(Some((nonce, is_block))) => {
+                log.info(format!("üíé Found potential solution with nonce: {} (is_block: {})", nonce, is_block), Some("Share"));
             }
             Ok(None) => {
                 debug!("‚è≠Ô∏è Round completed without finding a solution");
@@ -620,7 +967,78 @@ impl Miner {
                 log.error(format!("‚ùå Error during mining: {:?}", e), Some("Miner"));
             }
         }
-        
+
         Ok(())
     }
 }
+
+/// Abstraction over a mining backend so `Server` (and the genesis-mining
+/// loop) can dispatch a batch without caring whether it's running against
+/// an OpenCL device or the CPU fallback. [`Miner`] implements this by
+/// delegating to its existing inherent methods; see
+/// `crate::cpu_miner::CpuMiner` for the other implementation and
+/// [`MinerBackend`] for the enum `Server` actually stores.
+pub trait Backend {
+    /// Search this batch for a nonce; see `Miner::find_nonce`'s docs for the
+    /// `(nonce, is_block)` return shape.
+    fn find_nonce(&mut self, work: &Work, log: &Log) -> Result<Option<(u64, bool)>>;
+    fn num_nonces_per_search(&self) -> u64;
+    fn has_nonces_left(&self, work: &Work) -> bool;
+}
+
+impl Backend for Miner {
+    fn find_nonce(&mut self, work: &Work, log: &Log) -> Result<Option<(u64, bool)>> {
+        Miner::find_nonce(self, work, log)
+    }
+
+    fn num_nonces_per_search(&self) -> u64 {
+        Miner::num_nonces_per_search(self)
+    }
+
+    fn has_nonces_left(&self, work: &Work) -> bool {
+        Miner::has_nonces_left(self, work)
+    }
+}
+
+/// The mining backend bound to one `Server` device slot: either an OpenCL
+/// [`Miner`] or the CPU fallback, chosen once in `Server::from_config`
+/// depending on `mining_backend`/device availability and never swapped
+/// afterwards.
+pub enum MinerBackend {
+    Gpu(Miner),
+    Cpu(crate::cpu_miner::CpuMiner),
+}
+
+impl Backend for MinerBackend {
+    fn find_nonce(&mut self, work: &Work, log: &Log) -> Result<Option<(u64, bool)>> {
+        match self {
+            MinerBackend::Gpu(miner) => miner.find_nonce(work, log),
+            MinerBackend::Cpu(miner) => miner.find_nonce(work, log),
+        }
+    }
+
+    fn num_nonces_per_search(&self) -> u64 {
+        match self {
+            MinerBackend::Gpu(miner) => miner.num_nonces_per_search(),
+            MinerBackend::Cpu(miner) => miner.num_nonces_per_search(),
+        }
+    }
+
+    fn has_nonces_left(&self, work: &Work) -> bool {
+        match self {
+            MinerBackend::Gpu(miner) => miner.has_nonces_left(work),
+            MinerBackend::Cpu(miner) => miner.has_nonces_left(work),
+        }
+    }
+}
+
+impl MinerBackend {
+    /// OpenCL device indices this backend is bound to; empty for the CPU
+    /// fallback, which isn't tied to any GPU.
+    pub fn gpu_indices(&self) -> &[usize] {
+        match self {
+            MinerBackend::Gpu(miner) => miner.gpu_indices(),
+            MinerBackend::Cpu(_) => &[],
+        }
+    }
+}
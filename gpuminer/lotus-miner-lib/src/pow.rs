@@ -0,0 +1,219 @@
+// Proof-of-work helpers shared by the genesis miner and (future) block miner.
+// Implements the standard Bitcoin-style compact-bits target encoding.
+
+use sha2::{Digest, Sha256};
+
+/// Expand a compact `nBits` value into a 32-byte big-endian target.
+///
+/// Uses the standard scheme: `exp = n_bits >> 24`, `mant = n_bits & 0x007fffff`.
+/// The 0x00800000 sign bit is ignored (targets are never negative) since a
+/// well-formed node will never set it; if it is set we simply mask it off.
+pub fn nbits_to_target(n_bits: u32) -> [u8; 32] {
+    let exp = (n_bits >> 24) as i32;
+    let mant = n_bits & 0x007f_ffff;
+
+    let mut target = [0u8; 32];
+    if mant == 0 {
+        return target;
+    }
+
+    // target = mant * 256^(exp - 3), laid out big-endian in a 32-byte buffer.
+    if exp <= 3 {
+        let value = mant >> (8 * (3 - exp));
+        target[29] = ((value >> 16) & 0xff) as u8;
+        target[30] = ((value >> 8) & 0xff) as u8;
+        target[31] = (value & 0xff) as u8;
+    } else {
+        let shift = (exp - 3) as u32;
+        if shift < 32 {
+            let end = 31 - shift as usize;
+            if end >= 2 {
+                target[end - 2] = ((mant >> 16) & 0xff) as u8;
+                target[end - 1] = ((mant >> 8) & 0xff) as u8;
+                target[end] = (mant & 0xff) as u8;
+            }
+        }
+    }
+    target
+}
+
+/// The network's difficulty-1 target (PoW limit), expressed in compact form.
+/// Mirrors Bitcoin-derived chains' `powLimit`; no block may have a target
+/// looser (numerically larger) than this.
+pub const MAX_TARGET_BITS: u32 = 0x1d00ffff;
+
+/// Re-encode a 32-byte big-endian target into compact `nBits` form.
+///
+/// This is the inverse of [`nbits_to_target`]: find the minimal exponent so
+/// the most significant 3 mantissa bytes fit, then shift in an extra byte
+/// (growing the exponent by one) whenever the top mantissa byte's high bit
+/// is set, since that bit is reserved as the target's sign guard.
+pub fn target_to_nbits(target: &[u8; 32]) -> u32 {
+    // Index of the first non-zero byte (most significant first).
+    let first_nonzero = match target.iter().position(|&b| b != 0) {
+        Some(idx) => idx,
+        None => return 0,
+    };
+
+    let mut size = (32 - first_nonzero) as u32;
+    let mut mantissa_bytes = [0u8; 3];
+    for i in 0..3 {
+        let src = first_nonzero + i;
+        mantissa_bytes[i] = if src < 32 { target[src] } else { 0 };
+    }
+    let mut mantissa = u32::from_be_bytes([0, mantissa_bytes[0], mantissa_bytes[1], mantissa_bytes[2]]);
+
+    // The sign bit (0x00800000) must stay clear; if the top mantissa byte
+    // has its high bit set, shift the mantissa right a byte and bump size.
+    if mantissa & 0x0080_0000 != 0 {
+        mantissa >>= 8;
+        size += 1;
+    }
+
+    (size << 24) | (mantissa & 0x007f_ffff)
+}
+
+/// Compute the next block's target following the expanding/shrinking DAA.
+///
+/// `new_target = old_target * clamp(actual_timespan, expected/4, expected*4) / expected`,
+/// clamped below [`MAX_TARGET_BITS`].
+pub fn calculate_next_target(prev_target: &[u8; 32], actual_timespan: i64, expected_timespan: i64) -> [u8; 32] {
+    let min_timespan = expected_timespan / 4;
+    let max_timespan = expected_timespan * 4;
+    let clamped_timespan = actual_timespan.clamp(min_timespan, max_timespan).max(1) as u64;
+
+    let mut new_target = mul_div_target(prev_target, clamped_timespan, expected_timespan.max(1) as u64);
+
+    let max_target = nbits_to_target(MAX_TARGET_BITS);
+    if new_target > max_target {
+        new_target = max_target;
+    }
+    new_target
+}
+
+/// Compute the `nBits` expected for the next block, and report whether it is
+/// consistent with `claimed_bits` (e.g. the value embedded in a candidate
+/// header) so callers can reject a mismatched node-supplied target before
+/// spending any cycles mining against it.
+pub fn retarget_next_bits(prev_bits: u32, actual_timespan: i64, expected_timespan: i64, claimed_bits: u32) -> (u32, bool) {
+    let prev_target = nbits_to_target(prev_bits);
+    let next_target = calculate_next_target(&prev_target, actual_timespan, expected_timespan);
+    let next_bits = target_to_nbits(&next_target);
+    (next_bits, next_bits == claimed_bits)
+}
+
+/// Multiply a 256-bit big-endian unsigned target by `num` and divide by
+/// `den`, as used by the retarget formula. Saturates to the maximum 256-bit
+/// value on multiplication overflow; the caller clamps against the network's
+/// max target afterwards so this is always a safe upper bound.
+fn mul_div_target(target: &[u8; 32], num: u64, den: u64) -> [u8; 32] {
+    let limbs = to_limbs(target);
+
+    let mut mul_result = [0u64; 4];
+    let mut carry: u128 = 0;
+    for i in (0..4).rev() {
+        let product = limbs[i] as u128 * num as u128 + carry;
+        mul_result[i] = product as u64;
+        carry = product >> 64;
+    }
+    if carry != 0 {
+        return [0xffu8; 32];
+    }
+
+    let mut quotient = [0u64; 4];
+    let mut remainder: u128 = 0;
+    for i in 0..4 {
+        let dividend = (remainder << 64) | mul_result[i] as u128;
+        quotient[i] = (dividend / den as u128) as u64;
+        remainder = dividend % den as u128;
+    }
+    from_limbs(quotient)
+}
+
+/// Split a 32-byte big-endian value into four big-endian 64-bit limbs.
+fn to_limbs(bytes: &[u8; 32]) -> [u64; 4] {
+    let mut limbs = [0u64; 4];
+    for i in 0..4 {
+        limbs[i] = u64::from_be_bytes(bytes[i * 8..i * 8 + 8].try_into().unwrap());
+    }
+    limbs
+}
+
+/// Inverse of [`to_limbs`].
+fn from_limbs(limbs: [u64; 4]) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    for i in 0..4 {
+        bytes[i * 8..i * 8 + 8].copy_from_slice(&limbs[i].to_be_bytes());
+    }
+    bytes
+}
+
+/// Convert a Stratum pool "difficulty" (1.0 == the network's difficulty-1
+/// target) into the 32-byte big-endian share target miners must beat.
+///
+/// Mirrors the classic Stratum v1 convention: `target = target(diff=1) / difficulty`.
+/// A `difficulty` of `0.0` or less is treated as `1.0` so a buggy/missing
+/// `mining.set_difficulty` push can't hand out an unminable all-zero target.
+pub fn difficulty_to_target(difficulty: f64) -> [u8; 32] {
+    let difficulty = if difficulty > 0.0 { difficulty } else { 1.0 };
+    let diff_one_target = nbits_to_target(MAX_TARGET_BITS);
+
+    // Work in fixed-point: scale `difficulty` up so the division stays exact
+    // integer arithmetic, matching `mul_div_target`'s existing approach.
+    const SCALE: u64 = 1_000_000;
+    let scaled_difficulty = (difficulty * SCALE as f64).round().max(1.0) as u64;
+    mul_div_target(&diff_one_target, SCALE, scaled_difficulty)
+}
+
+/// Convert a difficulty value into a 32-byte big-endian target via
+/// `target = floor(2^256 / difficulty)`, the convention used by ethash-style
+/// miners (`maxUint256 / difficulty`) rather than Bitcoin's diff-1-relative
+/// [`difficulty_to_target`]. Useful for difficulty values defined against the
+/// full 256-bit space instead of this chain's `powLimit`. `difficulty` is
+/// rounded to the nearest integer no smaller than `1` (the fixed-point
+/// scaling `difficulty_to_target` uses for its smaller diff-1 target would
+/// overflow the 256-bit limbs here). A difficulty of `1` gives `2^256 - 1`,
+/// since `2^256` itself doesn't fit in 32 bytes.
+pub fn difficulty_to_target_u256(difficulty: f64) -> [u8; 32] {
+    let den = difficulty.round().max(1.0) as u64;
+
+    // floor((2^256 - 1) / den) via schoolbook long division over four
+    // big-endian 64-bit limbs, each already the maximum value.
+    let mut quotient = [0u64; 4];
+    let mut remainder: u128 = 0;
+    for i in 0..4 {
+        let dividend = (remainder << 64) | u64::MAX as u128;
+        quotient[i] = (dividend / den as u128) as u64;
+        remainder = dividend % den as u128;
+    }
+    // 2^256 = (2^256 - 1) + 1, so floor(2^256 / den) is one more than the
+    // quotient above exactly when den divides 2^256.
+    if den > 1 && remainder == den as u128 - 1 {
+        for limb in quotient.iter_mut().rev() {
+            let (sum, carry) = limb.overflowing_add(1);
+            *limb = sum;
+            if !carry {
+                break;
+            }
+        }
+    }
+    from_limbs(quotient)
+}
+
+/// Compute the double-SHA256 hash of a 160-byte header, reversed into the
+/// conventional big-endian display/comparison form, the byte order
+/// `nbits_to_target` produces. (`create_block`'s target is in the opposite
+/// order: `Miner::scan_buffer` compares it from its last byte first.)
+pub fn header_hash(header: &[u8; 160]) -> [u8; 32] {
+    let hash1 = Sha256::digest(&header[..]);
+    let hash2 = Sha256::digest(&hash1);
+    let mut hash: [u8; 32] = hash2.into();
+    hash.reverse();
+    hash
+}
+
+/// Compute the double-SHA256 block hash of a 160-byte header and check it
+/// against `target` using unsigned big-endian byte comparison.
+pub fn header_meets_target(header: &[u8; 160], target: &[u8; 32]) -> bool {
+    header_hash(header) <= *target
+}
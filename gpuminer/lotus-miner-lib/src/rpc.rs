@@ -0,0 +1,43 @@
+//! Typed classification of `submitblock` RPC responses, replacing
+//! `submit_block`'s previous reliance on string-matching the `result` field
+//! (e.g. comparing against the literal `"inconclusive"`).
+
+use serde_json::Value;
+
+/// How the node classified a submitted share/block, parsed from its
+/// `submitblock` response by [`classify_submit_response`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SubmitOutcome {
+    Accepted,
+    /// The node saw a different tip land first; an orphan race rather than a
+    /// sign of misconfiguration, distinguished so callers don't warn about it
+    /// the same way as a real rejection.
+    OrphanRace,
+    /// Rejected via a JSON-RPC `error` object or a non-empty, non-`"inconclusive"`
+    /// `result` reason string.
+    Rejected { code: Option<i64>, message: String },
+}
+
+/// Classify a parsed `submitblock` response's `result`/`error` fields. A
+/// missing or empty `result` with no `error` means accepted, mirroring both
+/// bitcoind's `submitblock` (null `result` on success) and the pool-mining
+/// response shape (empty-string `result` on success).
+pub fn classify_submit_response(result: Option<&str>, error: Option<&Value>) -> SubmitOutcome {
+    if let Some(error) = error {
+        let code = error.get("code").and_then(Value::as_i64);
+        let message = error
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown error")
+            .to_string();
+        return SubmitOutcome::Rejected { code, message };
+    }
+    match result {
+        None | Some("") => SubmitOutcome::Accepted,
+        Some("inconclusive") => SubmitOutcome::OrphanRace,
+        Some(reason) => SubmitOutcome::Rejected {
+            code: None,
+            message: reason.to_string(),
+        },
+    }
+}
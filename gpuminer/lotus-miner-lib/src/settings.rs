@@ -1,8 +1,12 @@
 use std::io::Write;
 use std::str::FromStr;
 use serde::{Deserialize, Serialize};
-use config::{Config, ConfigError, File};
-use crate::miner::KernelType;
+use config::{Config, ConfigError, Environment, File};
+use crate::metrics;
+use crate::metrics::MetricsSettings;
+use crate::work_notify;
+use crate::work_notify::WorkNotifySettings;
+use crate::miner::{GpuSelection, KernelType};
 
 // Custom implementation to allow string deserialization of KernelType
 impl FromStr for KernelType {
@@ -40,17 +44,205 @@ impl Serialize for KernelType {
     }
 }
 
+// Parse a `-g/--gpu-index` CLI value such as "0", "0,1,3", or "all" into the
+// same selection the config file's `gpu_index` key accepts.
+impl FromStr for GpuSelection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("all") {
+            return Ok(GpuSelection::All);
+        }
+        s.split(',')
+            .map(|part| {
+                part.trim()
+                    .parse::<u32>()
+                    .map_err(|_| format!("Invalid gpu_index value: {}. Expected an index, a comma-separated list of indices, or \"all\"", s))
+            })
+            .collect::<Result<Vec<u32>, _>>()
+            .map(GpuSelection::Indices)
+    }
+}
+
+// Custom implementation to allow `gpu_index` to be a single integer, a list
+// of integers, or the string "all" (one config key, several shapes).
+impl<'de> Deserialize<'de> for GpuSelection {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Single(i64),
+            Many(Vec<i64>),
+            Text(String),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Single(index) => Ok(GpuSelection::Indices(vec![index as u32])),
+            Raw::Many(indices) => Ok(GpuSelection::Indices(indices.into_iter().map(|i| i as u32).collect())),
+            Raw::Text(text) if text.eq_ignore_ascii_case("all") => Ok(GpuSelection::All),
+            Raw::Text(text) => Err(serde::de::Error::custom(format!(
+                "Invalid gpu_index value: {}. Expected an index, a list of indices, or \"all\"",
+                text
+            ))),
+        }
+    }
+}
+
+/// How the miner sources work: polling a node's `getrawunsolvedblock`/
+/// `submitblock` RPC, or a persistent Stratum v1 pool connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MiningProtocol {
+    Rpc,
+    Stratum,
+}
+
+/// Which mining backend `Server::from_config` should build: an OpenCL GPU
+/// per configured device, the CPU fallback, or (the default) pick CPU only
+/// when no OpenCL device is found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MiningBackend {
+    Auto,
+    Gpu,
+    Cpu,
+}
+
+impl FromStr for MiningBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(MiningBackend::Auto),
+            "gpu" => Ok(MiningBackend::Gpu),
+            "cpu" => Ok(MiningBackend::Cpu),
+            _ => Err(format!("Unknown mining_backend: {}. Valid options are 'auto', 'gpu', or 'cpu'", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for MiningBackend {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        FromStr::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for MiningBackend {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            MiningBackend::Auto => serializer.serialize_str("auto"),
+            MiningBackend::Gpu => serializer.serialize_str("gpu"),
+            MiningBackend::Cpu => serializer.serialize_str("cpu"),
+        }
+    }
+}
+
+impl Default for MiningBackend {
+    fn default() -> Self {
+        MiningBackend::Auto
+    }
+}
+
+impl FromStr for MiningProtocol {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "rpc" => Ok(MiningProtocol::Rpc),
+            "stratum" => Ok(MiningProtocol::Stratum),
+            _ => Err(format!("Unknown mining_protocol: {}. Valid options are 'rpc' or 'stratum'", s)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for MiningProtocol {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        FromStr::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for MiningProtocol {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            MiningProtocol::Rpc => serializer.serialize_str("rpc"),
+            MiningProtocol::Stratum => serializer.serialize_str("stratum"),
+        }
+    }
+}
+
 // Removed deprecated clap macros and APIs. All config loading is now handled directly below.
 
 pub const DEFAULT_URL: &str = "http://127.0.0.1:10604";
 pub const DEFAULT_USER: &str = "lotus";
 pub const DEFAULT_PASSWORD: &str = "lotus";
 pub const DEFAULT_RPC_POLL_INTERVAL: i64 = 3;
+pub const DEFAULT_RPC_CONNECT_TIMEOUT: i64 = 5;
+pub const DEFAULT_RPC_REQUEST_TIMEOUT: i64 = 15;
 pub const FOLDER_DIR: &str = ".lotus-miner";
 pub const DEFAULT_KERNEL_SIZE: i64 = 21;
 pub const DEFAULT_GPU_INDEX: i64 = 0;
 pub const DEFAULT_KERNEL_TYPE: KernelType = KernelType::LotusOG;
 pub const DEFAULT_GENESIS_MINING: bool = false;
+pub const DEFAULT_AUTOTUNE: bool = false;
+pub const DEFAULT_MINING_BACKEND: MiningBackend = MiningBackend::Auto;
+pub const DEFAULT_POOL_RECONNECT_INTERVAL: i64 = 10;
+pub const DEFAULT_MINING_PROTOCOL: MiningProtocol = MiningProtocol::Rpc;
+pub const DEFAULT_REPORT_INTERVAL: i64 = 20;
+/// Default deadline for `update_next_block`'s prefetch and `submit_block`'s
+/// submission awaits; see [`ConfigSettings::task_timeout`].
+pub const DEFAULT_TASK_TIMEOUT: i64 = 10;
+
+/// Stratum connection details for `pool_mining = true`, set via the `[pool]`
+/// table. `url` is validated as required in [`ConfigSettings::load`] when
+/// pool mining is enabled; all other fields are optional.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PoolSettings {
+    pub url: Option<String>,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    pub worker_name: Option<String>,
+    pub reconnect_interval: i64,
+}
+
+/// One entry in a farm profile's prioritized `[[pools]]` list: a structured
+/// alternative to `rpc_url`'s comma-separated string for setups that need
+/// per-endpoint credentials. Lower `priority` is tried first; entries are
+/// folded into `rpc_url`/`rpc_user`/`rpc_password` in [`ConfigSettings::load`]
+/// so the existing failover/Stratum-detection logic handles them unchanged.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PoolEndpoint {
+    pub url: String,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    #[serde(default)]
+    pub priority: i64,
+}
+
+/// Per-device override for a farm profile's `[[gpu]]` array. Any field left
+/// unset falls back to the top-level `kernel_size`/`kernel_type` default or
+/// `Server::from_config`'s built-in `local_work_size`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GpuProfile {
+    pub index: u32,
+    pub kernel_size: Option<i64>,
+    pub kernel_type: Option<KernelType>,
+    pub local_work_size: Option<i64>,
+}
 
 #[derive(Debug, Deserialize)]
 pub struct ConfigSettings {
@@ -58,29 +250,234 @@ pub struct ConfigSettings {
     pub rpc_user: String,
     pub rpc_password: String,
     pub rpc_poll_interval: i64,
+    pub rpc_connect_timeout: i64,
+    pub rpc_request_timeout: i64,
     pub mine_to_address: String,
     pub kernel_size: i64,
-    pub gpu_index: i64,
+    pub gpu_index: GpuSelection,
     pub pool_mining: bool,
     pub kernel_type: KernelType,
+    /// Selects how work is sourced: polling a node (`"rpc"`, the default) or
+    /// a persistent Stratum v1 pool connection (`"stratum"`), see
+    /// `Server::run`. Independent of `pool_mining`, which only controls how
+    /// found work is submitted/reported.
+    pub mining_protocol: MiningProtocol,
+    /// How often (seconds) the `Statistics` reporting task logs the
+    /// "shares accepted/rejected, H/s" summary. See `run_statistics_reporter`.
+    pub report_interval: i64,
+    /// Deadline (seconds) for a single `update_next_block` prefetch or
+    /// `submit_block` submission, so a stalled node/pool connection can't
+    /// wedge the mining loop indefinitely. See `with_task_timeout`.
+    pub task_timeout: i64,
     pub genesis_mining: bool,
     pub genesis_bits: Option<String>,
+    /// true to sweep `kernel_size`/`local_work_size` on each device at
+    /// startup and keep whichever combination peaks hashes/sec, instead of
+    /// using `kernel_size`/the farm profile's `local_work_size` as-is. See
+    /// `Miner::autotune`. Results are cached in `tuning.json` so a later run
+    /// with a cache hit for a device skips re-sweeping it.
+    #[serde(default)]
+    pub autotune: bool,
+    /// Which mining backend to use: "auto" (default, GPU if available, else
+    /// CPU), "gpu" (fail if no OpenCL device is found), or "cpu" (always use
+    /// the CPU fallback regardless of GPU availability). See
+    /// `Server::from_config` and `crate::cpu_miner::CpuMiner`.
+    #[serde(default)]
+    pub mining_backend: MiningBackend,
+    pub metrics: MetricsSettings,
+    /// Optional endpoint so external/secondary miner processes can pull the
+    /// current work and submit candidate headers back through this node
+    /// instead of each polling the upstream node/pool independently. See
+    /// `run_work_notify_server`.
+    pub work_notify: WorkNotifySettings,
+    pub notify_webhook_url: Option<String>,
+    pub notify_webhook_username: Option<String>,
+    pub pool: PoolSettings,
+    /// Farm profile: prioritized pool/RPC endpoints, folded into `rpc_url`
+    /// (and `rpc_user`/`rpc_password` from the highest-priority entry) by
+    /// [`ConfigSettings::load`]. Empty unless set via a custom `[[pools]]`
+    /// table, e.g. with `--config`.
+    #[serde(default)]
+    pub pools: Vec<PoolEndpoint>,
+    /// Farm profile: per-device `kernel_size`/`kernel_type`/`local_work_size`
+    /// overrides, set via `[[gpu]]`. Empty unless set via a custom config.
+    #[serde(default)]
+    pub gpu: Vec<GpuProfile>,
 }
 
 const DEFAULT_CONFIG_FILE_CONTENT: &str = r#"mine_to_address = "lotus_16PSJMStv9sve3DfhDpiwUCa7RtqkyNBoS8RjFZSt"
 rpc_url = "http://127.0.0.1:10604"
 rpc_poll_interval = 3
+rpc_connect_timeout = 5
+rpc_request_timeout = 15
 rpc_user = "lotus"
-rpc_password = "lotus"
+rpc_password = "__RPC_PASSWORD__"
 gpu_index = 0
 kernel_size = 23
 pool_mining = false
 kernel_type = "lotus_og"
+mining_protocol = "rpc"
+report_interval = 20
+task_timeout = 10
 genesis_mining = false
+autotune = false
+mining_backend = "auto"
+
+[metrics]
+enable = false
+host = "127.0.0.1"
+port = 9001
+
+[work_notify]
+enable = false
+host = "127.0.0.1"
+port = 9002
 "#;
 
+/// Per-field documentation inserted above each key when materializing the
+/// default `config.toml`, keyed by the exact field name (or `table.field` for
+/// keys inside a `[table]`, since e.g. `[metrics]` and `[work_notify]` both
+/// have their own `enable`/`host`/`port`) so it stays easy to keep in sync
+/// with `ConfigSettings`.
+const FIELD_COMMENTS: &[(&str, &str)] = &[
+    ("mine_to_address", "Lotus address credited with mined subsidy (solo mining) or pool payouts."),
+    ("rpc_url", "Lotus node RPC endpoint(s): one URL, or a comma-separated list to fail over across."),
+    ("rpc_poll_interval", "How often (seconds) to poll the node for new work via getrawunsolvedblock."),
+    ("rpc_connect_timeout", "Timeout (seconds) for establishing the RPC connection; after several consecutive failed requests the next endpoint is tried."),
+    ("rpc_request_timeout", "Timeout (seconds) for a full RPC request/response round trip."),
+    ("rpc_user", "Username for Lotus RPC authentication."),
+    ("rpc_password", "Password for Lotus RPC authentication. Randomly generated on first run."),
+    ("gpu_index", "OpenCL device(s) to mine on: a single index, a list like [0, 1, 2], or \"all\"."),
+    ("kernel_size", "Work batch size as a power of two (kernel_size = 2^n). Higher uses more GPU memory."),
+    ("pool_mining", "true to submit shares to a pool instead of solo-mining full blocks."),
+    ("kernel_type", "OpenCL kernel to use: \"lotus_og\" (default) or \"poclbm\"."),
+    ("mining_protocol", "How work is sourced: \"rpc\" (default, poll a node) or \"stratum\" (connect to a pool via [pool])."),
+    ("report_interval", "How often (seconds) to log the shares accepted/rejected and hashrate summary."),
+    ("task_timeout", "Deadline (seconds) for a single work-prefetch or share-submission attempt before it's treated as hung."),
+    ("genesis_mining", "true to mine a new genesis block instead of connecting to a running node."),
+    ("autotune", "true to sweep kernel_size/local_work_size per device at startup and keep the fastest combination."),
+    ("mining_backend", "Mining backend to use: \"auto\" (GPU if available, else CPU), \"gpu\", or \"cpu\"."),
+    ("metrics.enable", "true to expose a Prometheus-compatible /metrics endpoint on host:port."),
+    ("metrics.host", "Address the metrics HTTP endpoint listens on."),
+    ("metrics.port", "Port the metrics HTTP endpoint listens on."),
+    ("work_notify.enable", "true to expose the current work over /work and accept candidates on /submit."),
+    ("work_notify.host", "Address the work-notify HTTP endpoint listens on."),
+    ("work_notify.port", "Port the work-notify HTTP endpoint listens on."),
+    ("work_notify.notify_url", "URL to POST the current work to whenever the chain tip changes. Leave unset to only poll /work."),
+];
+
+/// Prepend each key's documentation from [`FIELD_COMMENTS`] as a `#` comment
+/// on the line above it, analogous to grin's `insert_comments` helper.
+/// Comments are ignored by the `config` crate's TOML parser, so the file
+/// stays fully round-trippable.
+fn insert_comments(content: &str) -> String {
+    let mut annotated = String::new();
+    let mut section = String::new();
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            section = trimmed.trim_matches(|c| c == '[' || c == ']').to_string();
+        } else {
+            let field = line.split('=').next().unwrap_or("").trim();
+            let key = if section.is_empty() {
+                field.to_string()
+            } else {
+                format!("{}.{}", section, field)
+            };
+            if let Some((_, comment)) = FIELD_COMMENTS.iter().find(|(candidate, _)| *candidate == key) {
+                annotated.push_str("# ");
+                annotated.push_str(comment);
+                annotated.push('\n');
+            }
+        }
+        annotated.push_str(line);
+        annotated.push('\n');
+    }
+    annotated
+}
+
+/// Number of characters drawn for a freshly generated `rpc_password`.
+const GENERATED_RPC_PASSWORD_LEN: usize = 32;
+
+/// Draw a fresh alphanumeric secret for the `rpc_password` written into a
+/// first-run `config.toml`, so every install gets its own credential instead
+/// of inheriting the static `"lotus"` default.
+fn generate_rpc_password() -> String {
+    use rand::{distributions::Alphanumeric, Rng};
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(GENERATED_RPC_PASSWORD_LEN)
+        .map(char::from)
+        .collect()
+}
+
+/// One device's auto-tuned `kernel_size`/`local_work_size`, as written to
+/// `tuning.json` by [`Miner::autotune`](crate::miner::Miner::autotune) so a
+/// later run with `autotune = true` can skip re-sweeping a device it already
+/// tuned.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TunedDevice {
+    pub gpu_index: u32,
+    pub kernel_size: u32,
+    pub local_work_size: i32,
+}
+
+const TUNING_CACHE_FILE: &str = "tuning.json";
+
+fn tuning_cache_path() -> Option<std::path::PathBuf> {
+    dirs::home_dir().map(|home| home.join(FOLDER_DIR).join(TUNING_CACHE_FILE))
+}
+
+/// Load the auto-tuning cache, empty if it doesn't exist yet or can't be
+/// read/parsed.
+pub fn load_tuning_cache() -> Vec<TunedDevice> {
+    let Some(path) = tuning_cache_path() else {
+        return Vec::new();
+    };
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Persist `device`'s tuned settings into the cache, replacing any existing
+/// entry for the same `gpu_index`.
+pub fn save_tuned_device(device: TunedDevice) {
+    let Some(path) = tuning_cache_path() else {
+        return;
+    };
+    let mut cache = load_tuning_cache();
+    cache.retain(|entry| entry.gpu_index != device.gpu_index);
+    cache.push(device);
+    if let Ok(content) = serde_json::to_string_pretty(&cache) {
+        if let Err(err) = std::fs::write(&path, content) {
+            eprintln!("Error: Couldn't write tuning cache {}: {}", path.to_string_lossy(), err);
+        }
+    }
+}
+
 impl ConfigSettings {
-    pub fn load(_expect_mine_to_address: bool) -> Result<Self, ConfigError> {
+    /// Look up this GPU's `[[gpu]]` farm-profile overrides, if any, falling
+    /// back to the top-level defaults for any field left unset.
+    pub fn gpu_profile(&self, gpu_index: u32) -> GpuProfile {
+        self.gpu
+            .iter()
+            .find(|profile| profile.index == gpu_index)
+            .cloned()
+            .unwrap_or(GpuProfile {
+                index: gpu_index,
+                kernel_size: None,
+                kernel_type: None,
+                local_work_size: None,
+            })
+    }
+
+    /// Load settings, optionally merging a custom TOML file (`--config`) on
+    /// top of the default `~/.lotus-miner/config.toml` before falling back
+    /// to environment variables. CLI flag overrides are applied afterwards,
+    /// in `main.rs`, so the precedence is: defaults < default config file <
+    /// `--config` file < environment < explicit CLI flags.
+    pub fn load(_expect_mine_to_address: bool, config_path: Option<&str>) -> Result<Self, ConfigError> {
         let mut s = Config::new();
 
         // Set defaults
@@ -90,6 +487,8 @@ impl ConfigSettings {
         };
         s.set_default("rpc_url", DEFAULT_URL)?;
         s.set_default("rpc_poll_interval", DEFAULT_RPC_POLL_INTERVAL)?;
+        s.set_default("rpc_connect_timeout", DEFAULT_RPC_CONNECT_TIMEOUT)?;
+        s.set_default("rpc_request_timeout", DEFAULT_RPC_REQUEST_TIMEOUT)?;
         s.set_default("rpc_user", DEFAULT_USER)?;
         s.set_default("rpc_password", DEFAULT_PASSWORD)?;
         s.set_default("kernel_size", DEFAULT_KERNEL_SIZE)?;
@@ -100,7 +499,26 @@ impl ConfigSettings {
             KernelType::LotusOG => "lotus_og",
             KernelType::POCLBM => "poclbm",
         })?;
+        s.set_default("mining_protocol", match DEFAULT_MINING_PROTOCOL {
+            MiningProtocol::Rpc => "rpc",
+            MiningProtocol::Stratum => "stratum",
+        })?;
+        s.set_default("report_interval", DEFAULT_REPORT_INTERVAL)?;
+        s.set_default("task_timeout", DEFAULT_TASK_TIMEOUT)?;
         s.set_default("genesis_mining", DEFAULT_GENESIS_MINING)?;
+        s.set_default("autotune", DEFAULT_AUTOTUNE)?;
+        s.set_default("mining_backend", match DEFAULT_MINING_BACKEND {
+            MiningBackend::Auto => "auto",
+            MiningBackend::Gpu => "gpu",
+            MiningBackend::Cpu => "cpu",
+        })?;
+        s.set_default("metrics.enable", metrics::DEFAULT_METRICS_ENABLE)?;
+        s.set_default("metrics.host", metrics::DEFAULT_METRICS_HOST)?;
+        s.set_default("metrics.port", metrics::DEFAULT_METRICS_PORT as i64)?;
+        s.set_default("work_notify.enable", work_notify::DEFAULT_WORK_NOTIFY_ENABLE)?;
+        s.set_default("work_notify.host", work_notify::DEFAULT_WORK_NOTIFY_HOST)?;
+        s.set_default("work_notify.port", work_notify::DEFAULT_WORK_NOTIFY_PORT as i64)?;
+        s.set_default("pool.reconnect_interval", DEFAULT_POOL_RECONNECT_INTERVAL)?;
 
         // Load config from file
         let default_config = home_dir;
@@ -116,9 +534,11 @@ impl ConfigSettings {
                     err
                 );
             }
+            let config_content = insert_comments(DEFAULT_CONFIG_FILE_CONTENT)
+                .replace("__RPC_PASSWORD__", &generate_rpc_password());
             match std::fs::File::create(&default_config_toml) {
                 Ok(mut file) => {
-                    if let Err(err) = file.write_all(DEFAULT_CONFIG_FILE_CONTENT.as_bytes())
+                    if let Err(err) = file.write_all(config_content.as_bytes())
                     {
                         eprintln!(
                             "Error: Couldn't write default config toml file {}: {}",
@@ -138,8 +558,99 @@ impl ConfigSettings {
         }
         s.merge(File::with_name(default_config_toml.to_str().unwrap()).required(false))?;
 
+        // A `--config` file layers on top of the default config.toml, for a
+        // reproducible farm profile instead of a long command line.
+        if let Some(path) = config_path {
+            s.merge(File::with_name(path).required(true))?;
+        }
+
+        // Environment overrides take precedence over the file, so deployments
+        // without a writable home directory (containers, systemd units) can
+        // still override any field, e.g. LOTUS_MINER_RPC_URL, LOTUS_MINER_GPU_INDEX.
+        // The separator is "__" (not "_") so it only splits on nested-section
+        // boundaries (e.g. LOTUS_MINER_METRICS__ENABLE -> metrics.enable);
+        // with a single "_" every flat multi-word field (rpc_url, gpu_index,
+        // kernel_type, ...) would get misparsed as a nested path and silently
+        // fail to override anything.
+        s.merge(Environment::with_prefix("LOTUS_MINER").separator("__"))?;
+
         // All CLI overrides are now handled in main.rs (lotus-miner-cli)
 
-        s.try_into()
+        let mut settings: ConfigSettings = s.try_into()?;
+
+        // A farm profile's `[[pools]]` list overrides rpc_url wholesale,
+        // highest-priority (lowest number) endpoint first, so the existing
+        // comma-separated failover chain and stratum+tcp:// detection both
+        // apply unchanged. The top endpoint's credentials win if set.
+        if !settings.pools.is_empty() {
+            let mut pools = settings.pools.clone();
+            pools.sort_by_key(|endpoint| endpoint.priority);
+            settings.rpc_url = pools.iter().map(|endpoint| endpoint.url.as_str()).collect::<Vec<_>>().join(",");
+            if let Some(ref user) = pools[0].user {
+                settings.rpc_user = user.clone();
+            }
+            if let Some(ref password) = pools[0].password {
+                settings.rpc_password = password.clone();
+            }
+        }
+
+        // Reject incoherent config up front rather than failing deep in the
+        // Stratum client once pool mining is already underway.
+        if settings.pool_mining && settings.pool.url.is_none() && settings.pools.is_empty() {
+            return Err(ConfigError::Message(
+                "pool_mining is enabled but no [pool] url or [[pools]] list is configured".to_string(),
+            ));
+        }
+
+        // `mining_protocol = "stratum"` needs a pool address from [pool],
+        // or (for configs written before `mining_protocol` existed) a
+        // `stratum+tcp://` rpc_url to fall back on.
+        if settings.mining_protocol == MiningProtocol::Stratum
+            && settings.pool.url.is_none()
+            && !settings.rpc_url.contains("stratum+tcp://")
+        {
+            return Err(ConfigError::Message(
+                "mining_protocol is \"stratum\" but no [pool] url is configured".to_string(),
+            ));
+        }
+
+        Ok(settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    // `ConfigSettings::load` reads `$HOME` (to find/create ~/.lotus-miner)
+    // and sets process-global env vars, so any test exercising it needs to
+    // be serialized against other such tests and pointed at a throwaway
+    // home directory instead of the developer's/CI's real one.
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn env_override_replaces_flat_field() {
+        let _guard = ENV_LOCK.lock().unwrap();
+
+        let temp_home = std::env::temp_dir().join(format!("lotus-miner-test-home-{}", std::process::id()));
+        std::fs::create_dir_all(&temp_home).expect("create temp home dir");
+        let previous_home = std::env::var_os("HOME");
+        std::env::set_var("HOME", &temp_home);
+
+        let overridden_url = "http://127.0.0.1:9999";
+        std::env::set_var("LOTUS_MINER_RPC_URL", overridden_url);
+
+        let result = ConfigSettings::load(false, None);
+
+        std::env::remove_var("LOTUS_MINER_RPC_URL");
+        match previous_home {
+            Some(home) => std::env::set_var("HOME", home),
+            None => std::env::remove_var("HOME"),
+        }
+        let _ = std::fs::remove_dir_all(&temp_home);
+
+        let settings = result.expect("load should succeed");
+        assert_eq!(settings.rpc_url, overridden_url);
     }
 }
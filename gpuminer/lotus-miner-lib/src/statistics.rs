@@ -0,0 +1,220 @@
+//! Aggregate mining statistics: hashrate and share counters, previously
+//! scattered across `Server`'s `metrics_nonces`/`last_total_nonces`/
+//! `hashrate_data_points` fields and ad hoc accept/reject logging inside
+//! `submit_block`. `submit_block` feeds outcomes in via `record_share`,
+//! `mine_some_nonces`/`stratum_dispatch_nonces` feed searched nonce counts in
+//! via `record_nonces`, and `run_statistics_reporter` periodically logs a summary.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+use tokio::sync::Mutex;
+
+use crate::logger::Log;
+use crate::miner::format_hashes;
+
+/// How `submit_block` classifies a submitted share/block, fed into
+/// [`Statistics::record_share`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareOutcome {
+    Accepted,
+    Rejected,
+    /// Rejected as stale: `"inconclusive"` from the node (an orphan race), or
+    /// a Stratum pool's stale-job/duplicate-share error. Tracked separately
+    /// since it isn't a sign of misconfiguration like other rejections.
+    Stale,
+}
+
+/// Session-cumulative and per-interval mining statistics, shared behind
+/// `Server::statistics`.
+pub struct Statistics {
+    submitted_shares: AtomicU64,
+    accepted_shares: AtomicU64,
+    rejected_shares: AtomicU64,
+    stale_shares: AtomicU64,
+    session_nonces: AtomicU64,
+    last_total_nonces: AtomicU64,
+    /// How many `update_next_block`/`submit_block` attempts have hit
+    /// `with_task_timeout`'s deadline. See `record_timeout`.
+    timeouts: AtomicU64,
+    hashrate_data_points: Mutex<Vec<(SystemTime, u64)>>,
+    /// The node/pool's rejection reason for the most recent non-stale
+    /// rejection, surfaced in `report()` so operators don't have to tail logs
+    /// to see why shares are failing.
+    last_rejection_reason: std::sync::Mutex<Option<String>>,
+    start_time: SystemTime,
+}
+
+impl Statistics {
+    pub fn new() -> Self {
+        Statistics {
+            submitted_shares: AtomicU64::new(0),
+            accepted_shares: AtomicU64::new(0),
+            rejected_shares: AtomicU64::new(0),
+            stale_shares: AtomicU64::new(0),
+            session_nonces: AtomicU64::new(0),
+            last_total_nonces: AtomicU64::new(0),
+            timeouts: AtomicU64::new(0),
+            hashrate_data_points: Mutex::new(Vec::new()),
+            last_rejection_reason: std::sync::Mutex::new(None),
+            start_time: SystemTime::now(),
+        }
+    }
+
+    /// Record that `count` more nonces were searched since the last call,
+    /// feeding the moving-average hashrate calculation.
+    pub fn record_nonces(&self, count: u64) {
+        self.session_nonces.fetch_add(count, Ordering::AcqRel);
+    }
+
+    /// Record a submitted share's outcome.
+    pub fn record_share(&self, outcome: ShareOutcome) {
+        self.submitted_shares.fetch_add(1, Ordering::Relaxed);
+        match outcome {
+            ShareOutcome::Accepted => self.accepted_shares.fetch_add(1, Ordering::Relaxed),
+            ShareOutcome::Rejected => self.rejected_shares.fetch_add(1, Ordering::Relaxed),
+            ShareOutcome::Stale => self.stale_shares.fetch_add(1, Ordering::Relaxed),
+        };
+    }
+
+    /// Record a rejected (non-stale) share along with the node/pool's
+    /// rejection reason, so it can be surfaced in the next `report()` line.
+    pub fn record_rejection(&self, reason: impl Into<String>) {
+        *self.last_rejection_reason.lock().unwrap() = Some(reason.into());
+        self.record_share(ShareOutcome::Rejected);
+    }
+
+    pub fn submitted(&self) -> u64 {
+        self.submitted_shares.load(Ordering::Relaxed)
+    }
+
+    pub fn accepted(&self) -> u64 {
+        self.accepted_shares.load(Ordering::Relaxed)
+    }
+
+    pub fn rejected(&self) -> u64 {
+        self.rejected_shares.load(Ordering::Relaxed)
+    }
+
+    pub fn stale(&self) -> u64 {
+        self.stale_shares.load(Ordering::Relaxed)
+    }
+
+    /// Record that an `update_next_block`/`submit_block` attempt hit
+    /// `with_task_timeout`'s deadline.
+    pub fn record_timeout(&self) {
+        self.timeouts.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn timeouts(&self) -> u64 {
+        self.timeouts.load(Ordering::Relaxed)
+    }
+
+    /// A 60-second moving average of `record_nonces`' hashes/second, with a
+    /// 15-second warm-up ramp so the first report after startup doesn't show
+    /// an unrealistic spike. Moved here unchanged from the old
+    /// `Server::calculate_moving_average_hashrate`.
+    pub async fn moving_average_hashrate(&self, log: &Log) -> f64 {
+        let now = SystemTime::now();
+        let current_total_nonces = self.session_nonces.load(Ordering::Acquire);
+        let previous_total = self.last_total_nonces.swap(current_total_nonces, Ordering::AcqRel);
+        let new_nonces = current_total_nonces.saturating_sub(previous_total);
+
+        let mut data_points = self.hashrate_data_points.lock().await;
+        data_points.push((now, new_nonces));
+
+        let cutoff = now.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default() - Duration::from_secs(60);
+        data_points.retain(|(time, _)| {
+            time.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default() >= cutoff
+        });
+
+        let mut total_nonces = 0u64;
+        let oldest_timestamp = data_points.first().map(|(time, _)| *time).unwrap_or(now);
+        for (_, nonces) in data_points.iter() {
+            total_nonces = total_nonces.saturating_add(*nonces);
+        }
+
+        let time_span = now
+            .duration_since(oldest_timestamp)
+            .unwrap_or_default()
+            .as_secs_f64()
+            .max(0.1);
+        let raw_hashrate = total_nonces as f64 / time_span;
+
+        if time_span < 15.0 {
+            let warm_up_factor = (time_span / 15.0).min(1.0);
+            let single_point_rate = if data_points.len() > 1 {
+                let (time1, _) = data_points[data_points.len() - 1];
+                let (time2, nonces2) = data_points[data_points.len() - 2];
+                let point_time_diff = time1.duration_since(time2).unwrap_or_default().as_secs_f64().max(0.1);
+                (nonces2 as f64) / point_time_diff
+            } else {
+                raw_hashrate
+            };
+            let capped_rate = single_point_rate.min(3_000_000_000.0); // Cap at 3 GH/s initially
+            let result = capped_rate * (1.0 - warm_up_factor) + raw_hashrate * warm_up_factor;
+
+            log.debug(
+                format!(
+                    "Stabilizing hashrate during {:.1}s warm-up period: raw {:.2} GH/s → stabilized {:.2} GH/s ({}% warm-up)",
+                    time_span,
+                    raw_hashrate / 1_000_000_000.0,
+                    result / 1_000_000_000.0,
+                    (warm_up_factor * 100.0) as u32
+                ),
+                Some("Hashrate"),
+            );
+            result
+        } else {
+            raw_hashrate
+        }
+    }
+
+    /// Accepted shares per minute since the process started.
+    fn share_rate_per_min(&self) -> f64 {
+        let elapsed_mins = self.start_time.elapsed().unwrap_or(Duration::from_secs(1)).as_secs_f64().max(1.0) / 60.0;
+        self.accepted() as f64 / elapsed_mins
+    }
+
+    /// Fraction of submitted shares rejected, including stale ones. `0.0`
+    /// until the first share is submitted.
+    fn reject_ratio(&self) -> f64 {
+        let submitted = self.submitted();
+        if submitted == 0 {
+            return 0.0;
+        }
+        (self.rejected() + self.stale()) as f64 / submitted as f64
+    }
+
+    /// Log the periodic "shares accepted X, rejected Y, Z H/s" summary, and
+    /// feed the hashrate into `Log::report_hashrate`'s own history.
+    pub async fn report(&self, log: &Log) {
+        let hashrate = self.moving_average_hashrate(log).await;
+        log.report_hashrate(hashrate);
+        let last_rejection_reason = self.last_rejection_reason.lock().unwrap().clone();
+        let reason_suffix = match last_rejection_reason {
+            Some(reason) => format!(" (last reject: {})", reason),
+            None => String::new(),
+        };
+        log.info(
+            format!(
+                "📊 Shares: {} accepted, {} rejected, {} stale, {} timed out ({:.2}/min, {:.1}% rejected) — {}/s{}",
+                self.accepted(),
+                self.rejected(),
+                self.stale(),
+                self.timeouts(),
+                self.share_rate_per_min(),
+                self.reject_ratio() * 100.0,
+                format_hashes(hashrate as u64),
+                reason_suffix,
+            ),
+            Some("Stats"),
+        );
+    }
+}
+
+impl Default for Statistics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
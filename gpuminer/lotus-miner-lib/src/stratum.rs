@@ -0,0 +1,375 @@
+// Stratum v1 pool client for `--poolmining` when the `-a` URL uses a
+// `stratum+tcp://` scheme, as an alternative to the HTTP
+// `getrawunsolvedblock`/`submitblock` RPC path used against a node directly.
+//
+// Stratum v1 was designed around Bitcoin-style headers, so a few Lotus
+// header fields (nHeight, hashEpochBlock, vSize, hashExtendedMetadata) and
+// the full 64-bit nNonce simply have no slot on the wire; see
+// `build_job_header` for how that gap is handled.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use thiserror::Error;
+
+use crate::genesis_miner::{build_block_header, combine_merkle_pair, compute_merkle_leaf, compute_serialize_hash};
+
+/// How long the initial `mining.subscribe`/`mining.authorize` handshake is
+/// allowed to block waiting for the pool to reply.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long `StratumClient::try_next_event` blocks waiting for a push
+/// before giving the caller a chance to keep mining the current job.
+const POLL_TIMEOUT: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Error)]
+pub enum StratumError {
+    #[error("Failed to connect to pool at {0}: {1}")]
+    Connect(String, std::io::Error),
+
+    #[error("Lost connection to pool: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Pool sent a malformed message: {0}")]
+    Malformed(String),
+
+    #[error("Pool rejected {0}: {1}")]
+    Rejected(&'static str, String),
+}
+
+/// A job announced by the pool via `mining.notify`.
+#[derive(Debug, Clone)]
+pub struct StratumJob {
+    pub job_id: String,
+    pub prev_hash: [u8; 32],
+    pub coinb1: Vec<u8>,
+    pub coinb2: Vec<u8>,
+    pub merkle_branch: Vec<[u8; 32]>,
+    /// Bitcoin-style block version. Lotus headers have no equivalent field
+    /// (`nHeaderVersion` is a fixed single byte, always `1`); kept only so
+    /// callers can log/inspect what the pool sent.
+    pub version: u32,
+    pub n_bits: u32,
+    pub n_time: u32,
+    pub clean_jobs: bool,
+}
+
+/// A message pushed by the pool, or the delayed reply to a `mining.submit`.
+pub enum StratumEvent {
+    Job(StratumJob),
+    SetDifficulty(f64),
+    SubmitResult { accepted: bool, code: Option<i64>, detail: Option<String> },
+}
+
+/// Standard Stratum v1 `mining.submit` error codes (as used by e.g. ckpool/
+/// cgminer), identifying a rejection as a stale/duplicate job rather than an
+/// actually-invalid share. Exposed so callers can decide whether a rejection
+/// warrants refreshing work or warning about a miner bug.
+pub const STRATUM_ERR_JOB_NOT_FOUND: i64 = 21;
+pub const STRATUM_ERR_DUPLICATE_SHARE: i64 = 22;
+
+/// Whether a `mining.submit` error `code` indicates the job was stale/
+/// duplicate (the pool already moved on) rather than the share itself being
+/// invalid.
+pub fn is_stale_error_code(code: Option<i64>) -> bool {
+    matches!(code, Some(STRATUM_ERR_JOB_NOT_FOUND) | Some(STRATUM_ERR_DUPLICATE_SHARE))
+}
+
+/// A persistent Stratum v1 connection to a mining pool.
+///
+/// Speaks the line-delimited JSON-RPC protocol directly over a blocking
+/// `TcpStream`; callers drive it from a `tokio::task::spawn_blocking` task,
+/// the same way `Miner::find_nonce` is driven from one.
+pub struct StratumClient {
+    writer: TcpStream,
+    reader: BufReader<TcpStream>,
+    next_id: u64,
+    pending_submit_id: Option<u64>,
+    pub extranonce1: Vec<u8>,
+    pub extranonce2_size: usize,
+}
+
+impl StratumClient {
+    /// Connect to `addr` (the `stratum+tcp://` URL with its scheme
+    /// stripped) and complete the `mining.subscribe`/`mining.authorize`
+    /// handshake.
+    pub fn connect(addr: &str, worker: &str, password: &str) -> Result<Self, StratumError> {
+        let writer = TcpStream::connect(addr).map_err(|e| StratumError::Connect(addr.to_string(), e))?;
+        writer.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+        let reader = BufReader::new(writer.try_clone()?);
+
+        let mut client = StratumClient {
+            writer,
+            reader,
+            next_id: 1,
+            pending_submit_id: None,
+            extranonce1: Vec::new(),
+            extranonce2_size: 4,
+        };
+        client.subscribe()?;
+        client.authorize(worker, password)?;
+
+        // Handshake is done; switch to short-lived reads so the caller's
+        // event loop can interleave polling the socket with mining rounds
+        // instead of blocking indefinitely on the next pool push.
+        client.reader.get_ref().set_read_timeout(Some(POLL_TIMEOUT))?;
+        Ok(client)
+    }
+
+    fn send(&mut self, method: &str, params: Value) -> Result<u64, StratumError> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let mut line = json!({"id": id, "method": method, "params": params}).to_string();
+        line.push('\n');
+        self.writer.write_all(line.as_bytes())?;
+        self.writer.flush()?;
+        Ok(id)
+    }
+
+    /// Block (up to `HANDSHAKE_TIMEOUT`) for one JSON-RPC line.
+    fn read_value(&mut self) -> Result<Value, StratumError> {
+        let mut line = String::new();
+        let bytes_read = self.reader.read_line(&mut line)?;
+        if bytes_read == 0 {
+            return Err(StratumError::Io(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "pool closed the connection",
+            )));
+        }
+        serde_json::from_str(line.trim())
+            .map_err(|e| StratumError::Malformed(format!("{}: {}", e, line.trim())))
+    }
+
+    /// Like `read_value`, but a read that times out (no push pending within
+    /// `POLL_TIMEOUT`) returns `Ok(None)` instead of an error.
+    ///
+    /// Note: if a line is only partially delivered when the timeout fires,
+    /// the partial bytes already consumed into `BufReader` are dropped along
+    /// with the rest of that line on the next call. In practice Stratum
+    /// lines are short enough to arrive in a single read, so this is a
+    /// pragmatic simplification rather than a fully framed reader.
+    fn try_read_value(&mut self) -> Result<Option<Value>, StratumError> {
+        let mut line = String::new();
+        match self.reader.read_line(&mut line) {
+            Ok(0) => Err(StratumError::Io(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "pool closed the connection",
+            ))),
+            Ok(_) => serde_json::from_str(line.trim())
+                .map(Some)
+                .map_err(|e| StratumError::Malformed(format!("{}: {}", e, line.trim()))),
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock || e.kind() == std::io::ErrorKind::TimedOut => Ok(None),
+            Err(e) => Err(StratumError::Io(e)),
+        }
+    }
+
+    fn subscribe(&mut self) -> Result<(), StratumError> {
+        self.send("mining.subscribe", json!(["lotus-gpu-miner"]))?;
+        let response = self.read_value()?;
+        if let Some(error) = response.get("error").filter(|e| !e.is_null()) {
+            return Err(StratumError::Rejected("mining.subscribe", error.to_string()));
+        }
+        let result = response
+            .get("result")
+            .ok_or_else(|| StratumError::Malformed("subscribe response missing result".to_string()))?;
+
+        // result = [subscriptions, extranonce1, extranonce2_size]
+        let extranonce1_hex = result
+            .get(1)
+            .and_then(Value::as_str)
+            .ok_or_else(|| StratumError::Malformed("subscribe result missing extranonce1".to_string()))?;
+        self.extranonce1 = hex::decode(extranonce1_hex).map_err(|e| StratumError::Malformed(e.to_string()))?;
+        self.extranonce2_size = result
+            .get(2)
+            .and_then(Value::as_u64)
+            .ok_or_else(|| StratumError::Malformed("subscribe result missing extranonce2_size".to_string()))?
+            as usize;
+        Ok(())
+    }
+
+    fn authorize(&mut self, worker: &str, password: &str) -> Result<(), StratumError> {
+        self.send("mining.authorize", json!([worker, password]))?;
+        let response = self.read_value()?;
+        if let Some(error) = response.get("error").filter(|e| !e.is_null()) {
+            return Err(StratumError::Rejected("mining.authorize", error.to_string()));
+        }
+        match response.get("result").and_then(Value::as_bool) {
+            Some(true) => Ok(()),
+            _ => Err(StratumError::Rejected(
+                "mining.authorize",
+                "pool refused worker credentials".to_string(),
+            )),
+        }
+    }
+
+    /// Poll for the next pool push (or a delayed `mining.submit` reply),
+    /// without blocking past `POLL_TIMEOUT` if nothing has arrived.
+    pub fn try_next_event(&mut self) -> Result<Option<StratumEvent>, StratumError> {
+        let value = match self.try_read_value()? {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+
+        if let Some(method) = value.get("method").and_then(Value::as_str) {
+            let params = value.get("params").cloned().unwrap_or(Value::Null);
+            return Ok(Some(match method {
+                "mining.notify" => StratumEvent::Job(parse_job(&params)?),
+                "mining.set_difficulty" => {
+                    let difficulty = params
+                        .get(0)
+                        .and_then(Value::as_f64)
+                        .ok_or_else(|| StratumError::Malformed("set_difficulty missing difficulty".to_string()))?;
+                    StratumEvent::SetDifficulty(difficulty)
+                }
+                // Unrecognized push (e.g. `client.reconnect`); nothing for
+                // the mining loop to act on.
+                _ => return Ok(None),
+            }));
+        }
+
+        if let Some(id) = value.get("id").and_then(Value::as_u64) {
+            if self.pending_submit_id == Some(id) {
+                self.pending_submit_id = None;
+                let error = value.get("error").filter(|e| !e.is_null());
+                let accepted = error.is_none() && value.get("result").and_then(Value::as_bool).unwrap_or(true);
+                let code = error.and_then(|e| e.get(0)).and_then(Value::as_i64);
+                return Ok(Some(StratumEvent::SubmitResult {
+                    accepted,
+                    code,
+                    detail: error.map(|e| e.to_string()),
+                }));
+            }
+        }
+
+        // A reply to something we're not tracking (or an id we've already
+        // resolved); nothing actionable for the mining loop.
+        Ok(None)
+    }
+
+    /// Submit a found nonce for `job_id`. Doesn't block for the pool's
+    /// accept/reject reply; that arrives later through `try_next_event`.
+    pub fn submit(
+        &mut self,
+        worker: &str,
+        job_id: &str,
+        extranonce2: &[u8],
+        n_time: u32,
+        nonce: u32,
+    ) -> Result<(), StratumError> {
+        let id = self.send(
+            "mining.submit",
+            json!([
+                worker,
+                job_id,
+                hex::encode(extranonce2),
+                format!("{:08x}", n_time),
+                format!("{:08x}", nonce),
+            ]),
+        )?;
+        self.pending_submit_id = Some(id);
+        Ok(())
+    }
+}
+
+fn parse_job(params: &Value) -> Result<StratumJob, StratumError> {
+    let get_str = |i: usize| {
+        params
+            .get(i)
+            .and_then(Value::as_str)
+            .ok_or_else(|| StratumError::Malformed(format!("mining.notify missing field {}", i)))
+    };
+    let get_hex = |i: usize| -> Result<Vec<u8>, StratumError> {
+        hex::decode(get_str(i)?).map_err(|e| StratumError::Malformed(e.to_string()))
+    };
+
+    let job_id = get_str(0)?.to_string();
+
+    // The pool sends prevhash in big-endian display form, same as
+    // `getrawunsolvedblock`'s target field; reverse it into Lotus's
+    // internal (little-endian) header storage order (see `create_block`).
+    let mut prev_hash: [u8; 32] = get_hex(1)?
+        .try_into()
+        .map_err(|_| StratumError::Malformed("prevhash is not 32 bytes".to_string()))?;
+    prev_hash.reverse();
+
+    let coinb1 = get_hex(2)?;
+    let coinb2 = get_hex(3)?;
+
+    let branch_values = params
+        .get(4)
+        .and_then(Value::as_array)
+        .ok_or_else(|| StratumError::Malformed("mining.notify missing merkle_branch".to_string()))?;
+    let mut merkle_branch = Vec::with_capacity(branch_values.len());
+    for entry in branch_values {
+        let hex_str = entry
+            .as_str()
+            .ok_or_else(|| StratumError::Malformed("merkle_branch entry is not a string".to_string()))?;
+        let bytes = hex::decode(hex_str).map_err(|e| StratumError::Malformed(e.to_string()))?;
+        merkle_branch.push(
+            bytes
+                .try_into()
+                .map_err(|_| StratumError::Malformed("merkle_branch entry is not 32 bytes".to_string()))?,
+        );
+    }
+
+    let version = u32::from_str_radix(get_str(5)?, 16).map_err(|e| StratumError::Malformed(e.to_string()))?;
+    let n_bits = u32::from_str_radix(get_str(6)?, 16).map_err(|e| StratumError::Malformed(e.to_string()))?;
+    let n_time = u32::from_str_radix(get_str(7)?, 16).map_err(|e| StratumError::Malformed(e.to_string()))?;
+    let clean_jobs = params.get(8).and_then(Value::as_bool).unwrap_or(false);
+
+    Ok(StratumJob {
+        job_id,
+        prev_hash,
+        coinb1,
+        coinb2,
+        merkle_branch,
+        version,
+        n_bits,
+        n_time,
+        clean_jobs,
+    })
+}
+
+/// Assemble a full 160-byte Lotus header for `job`, given this client's
+/// `extranonce1` and a per-attempt `extranonce2`. Returns the header (with
+/// nonce zeroed, ready for `Work::from_header`/`Work::set_big_nonce`) and
+/// the coinbase transaction hash, for logging.
+///
+/// Stratum v1 carries no slot for Lotus's `nHeight`, `hashEpochBlock`,
+/// `vSize`, or `hashExtendedMetadata` header fields (Bitcoin's header has no
+/// equivalents), so those are left at their spec-default zero here. A pool
+/// wanting shares that round-trip back into a submittable `lotusd` block
+/// would need a Lotus-aware extension to the protocol to carry them; this is
+/// sufficient for the proof-of-work search itself, which only hashes the
+/// header as a whole.
+pub(crate) fn build_job_header(client: &StratumClient, job: &StratumJob, extranonce2: &[u8]) -> ([u8; 160], [u8; 32]) {
+    let mut coinbase = Vec::with_capacity(job.coinb1.len() + client.extranonce1.len() + extranonce2.len() + job.coinb2.len());
+    coinbase.extend_from_slice(&job.coinb1);
+    coinbase.extend_from_slice(&client.extranonce1);
+    coinbase.extend_from_slice(extranonce2);
+    coinbase.extend_from_slice(&job.coinb2);
+    let coinbase_hash = compute_serialize_hash(&coinbase);
+
+    // A version-1 coinbase's `tx.GetId()` equals its `tx.GetHash()` (see
+    // `Transaction::get_id`), so the coinbase leaf folds the same way as the
+    // genesis coinbase's.
+    let mut root = compute_merkle_leaf(coinbase_hash, coinbase_hash);
+    for sibling in &job.merkle_branch {
+        root = combine_merkle_pair(root, *sibling);
+    }
+
+    let header = build_block_header(
+        job.prev_hash,
+        job.n_bits,
+        job.n_time as u64,
+        0, // nNonce filled in by `Work::set_big_nonce` during mining
+        0, // nHeight: not carried by Stratum v1, see doc comment above
+        [0u8; 32], // hashEpochBlock: not carried by Stratum v1
+        root,
+        [0u8; 32], // hashExtendedMetadata: not carried by Stratum v1
+        0,         // vSize: not carried by Stratum v1
+    );
+    (header, coinbase_hash)
+}
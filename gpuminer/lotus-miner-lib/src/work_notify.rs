@@ -0,0 +1,50 @@
+//! Optional work-notification endpoint, configured via the `[work_notify]`
+//! table in `config.toml`. Lets external/secondary miner processes pull the
+//! current work and submit candidate headers back through this node, instead
+//! of each one polling the upstream node/pool independently. Mirrors the
+//! external-miner `getWork`/`submitWork` pattern. Keeping the config type and
+//! payload shapes here (rather than on `Server`) matches how `metrics.rs`
+//! keeps its own endpoint's types separate from the server plumbing.
+
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_WORK_NOTIFY_ENABLE: bool = false;
+pub const DEFAULT_WORK_NOTIFY_HOST: &str = "127.0.0.1";
+pub const DEFAULT_WORK_NOTIFY_PORT: u16 = 9002;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkNotifySettings {
+    pub enable: bool,
+    pub host: String,
+    pub port: u16,
+    /// URL POSTed a [`WorkPayload`] whenever `update_next_block` installs a
+    /// new chain tip. Optional: external miners can just poll `/work` instead.
+    pub notify_url: Option<String>,
+}
+
+/// The current work, as served by `GET /work` and POSTed to `notify_url`.
+/// `header` has the nonce field left at whatever the node most recently saw
+/// (callers are expected to overwrite it while searching); `body` is needed
+/// alongside a winning `header` to reassemble a full block for `/submit`.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkPayload {
+    pub header: String,
+    pub body: String,
+    pub target: String,
+    pub height: i32,
+    pub extra_nonce: u64,
+}
+
+/// Body of a `POST /submit` request: a candidate header with the winning
+/// nonce filled in, matched back up against the block body/target this node
+/// last handed out as `WorkPayload::body`/`target`.
+#[derive(Debug, Deserialize)]
+pub struct SubmitPayload {
+    pub header: String,
+}
+
+/// Render a payload as JSON, for both the `/work` response and the outbound
+/// `notify_url` POST body.
+pub fn render_json(payload: &WorkPayload) -> String {
+    serde_json::to_string(payload).unwrap_or_else(|_| "{}".to_string())
+}
@@ -0,0 +1,185 @@
+//! Registry of long-running worker loops (the RPC poll loop, the mining
+//! loop, the Stratum controller/dispatcher), so an operator can list each
+//! one's state and last-progress time and pause/resume/stop it at runtime
+//! instead of only watching log output. Mirrors `statistics.rs`: plain
+//! atomics for the state callers poll, plus one `tokio::sync::watch`
+//! channel per worker carrying the operator's command.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use tokio::sync::watch;
+
+/// What a worker is doing right now, as reported by [`WorkerRegistry::list`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkerState {
+    Active,
+    Idle,
+    Stopping,
+    Dead,
+}
+
+impl WorkerState {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => WorkerState::Active,
+            1 => WorkerState::Idle,
+            2 => WorkerState::Stopping,
+            _ => WorkerState::Dead,
+        }
+    }
+}
+
+/// An operator command, delivered to a worker through its `watch` channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorkerCommand {
+    Run,
+    Pause,
+    Stop,
+}
+
+/// A snapshot of one worker's status, as returned by a `list-workers` query.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerStatus {
+    pub name: String,
+    pub state: WorkerState,
+    pub seconds_since_progress: u64,
+}
+
+/// The handle a worker loop holds onto itself: lets it report progress, and
+/// lets it check for an operator's pause/stop command before each batch of
+/// work (e.g. each `find_nonce` dispatch or RPC poll).
+pub struct WorkerHandle {
+    state: Arc<AtomicU8>,
+    last_progress: Arc<AtomicU64>,
+    command_rx: watch::Receiver<WorkerCommand>,
+}
+
+impl WorkerHandle {
+    /// Record that this worker made progress since the last call (e.g.
+    /// dispatched a batch of nonces, or completed a poll), resetting its
+    /// seconds-since-progress to zero.
+    pub fn progress(&self) {
+        self.last_progress.store(now_unix_secs(), Ordering::Relaxed);
+    }
+
+    fn set_state(&self, state: WorkerState) {
+        self.state.store(state as u8, Ordering::Relaxed);
+    }
+
+    /// Block on the operator's watch channel before the next batch of work:
+    /// returns `Ok(())` once `Run` is observed (recording `Active`), or
+    /// `Err(())` once `Stop` is observed, so the caller's loop can exit
+    /// cleanly instead of running forever with no way to shut it down.
+    pub async fn wait_if_paused(&mut self) -> Result<(), ()> {
+        loop {
+            match *self.command_rx.borrow() {
+                WorkerCommand::Run => {
+                    self.set_state(WorkerState::Active);
+                    return Ok(());
+                }
+                WorkerCommand::Stop => {
+                    self.set_state(WorkerState::Stopping);
+                    return Err(());
+                }
+                WorkerCommand::Pause => {
+                    self.set_state(WorkerState::Idle);
+                }
+            }
+            if self.command_rx.changed().await.is_err() {
+                // The registry (and with it every `Sender`) is gone; nothing
+                // left to coordinate with, so just carry on running.
+                return Ok(());
+            }
+        }
+    }
+
+    /// Mark the worker as no longer running, e.g. right before its loop
+    /// function returns.
+    pub fn mark_dead(&self) {
+        self.set_state(WorkerState::Dead);
+    }
+}
+
+struct WorkerEntry {
+    state: Arc<AtomicU8>,
+    last_progress: Arc<AtomicU64>,
+    command_tx: watch::Sender<WorkerCommand>,
+}
+
+/// Registry of every long-running worker loop, shared on `Server` so an
+/// admin RPC/CLI command can list/pause/resume/stop workers by name. See
+/// `Server::list_workers`/`Server::control_worker`.
+#[derive(Default)]
+pub struct WorkerRegistry {
+    workers: Mutex<HashMap<String, WorkerEntry>>,
+}
+
+impl WorkerRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a new worker loop under `name`, returning the handle it
+    /// should hold onto for the rest of its life. Re-registering the same
+    /// name (e.g. a reconnect loop restarting) replaces the previous entry.
+    pub fn register(&self, name: impl Into<String>) -> WorkerHandle {
+        let state = Arc::new(AtomicU8::new(WorkerState::Active as u8));
+        let last_progress = Arc::new(AtomicU64::new(now_unix_secs()));
+        let (command_tx, command_rx) = watch::channel(WorkerCommand::Run);
+        self.workers.lock().unwrap().insert(
+            name.into(),
+            WorkerEntry {
+                state: Arc::clone(&state),
+                last_progress: Arc::clone(&last_progress),
+                command_tx,
+            },
+        );
+        WorkerHandle { state, last_progress, command_rx }
+    }
+
+    /// Send a command to a named worker; `false` if no worker is registered
+    /// under that name.
+    pub fn send_command(&self, name: &str, command: WorkerCommand) -> bool {
+        match self.workers.lock().unwrap().get(name) {
+            Some(entry) => entry.command_tx.send(command).is_ok(),
+            None => false,
+        }
+    }
+
+    pub fn pause(&self, name: &str) -> bool {
+        self.send_command(name, WorkerCommand::Pause)
+    }
+
+    pub fn resume(&self, name: &str) -> bool {
+        self.send_command(name, WorkerCommand::Run)
+    }
+
+    pub fn stop(&self, name: &str) -> bool {
+        self.send_command(name, WorkerCommand::Stop)
+    }
+
+    /// Every registered worker's name, state, and seconds since its last
+    /// reported progress, for a `list-workers` query.
+    pub fn list(&self) -> Vec<WorkerStatus> {
+        let now = now_unix_secs();
+        self.workers
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, entry)| WorkerStatus {
+                name: name.clone(),
+                state: WorkerState::from_u8(entry.state.load(Ordering::Relaxed)),
+                seconds_since_progress: now.saturating_sub(entry.last_progress.load(Ordering::Relaxed)),
+            })
+            .collect()
+    }
+}
+
+fn now_unix_secs() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or(Duration::ZERO).as_secs()
+}